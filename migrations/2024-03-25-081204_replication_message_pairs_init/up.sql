@@ -0,0 +1,15 @@
+DROP TABLE IF EXISTS public.replication_message_pairs;
+CREATE TABLE public.replication_message_pairs
+(
+    id           bigserial                           NOT NULL,
+    from_guild   bigint                              NOT NULL,
+    from_channel bigint                              NOT NULL,
+    from_message bigint                              NOT NULL,
+    to_guild     bigint                              NOT NULL,
+    to_channel   bigint                              NOT NULL,
+    to_message   bigint                              NOT NULL,
+    created_at   TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT replication_message_pairs_pk PRIMARY KEY (id)
+);
+
+CREATE INDEX replication_message_pairs_from_idx ON public.replication_message_pairs (from_channel, from_message);
@@ -0,0 +1,4 @@
+ALTER TABLE public.replications_forum_pairs
+    ADD on_message_delete VARCHAR(16) DEFAULT 'delete' NOT NULL;
+ALTER TABLE public.replications_forum_pairs
+    ADD CONSTRAINT replications_forum_pairs_on_message_delete_check CHECK (on_message_delete IN ('delete', 'mark', 'keep'));
@@ -0,0 +1 @@
+ALTER TABLE public.replications_forum_pairs DROP COLUMN IF EXISTS attachment_fallback;
@@ -0,0 +1,4 @@
+ALTER TABLE public.replications_forum_pairs
+    ADD attachment_fallback VARCHAR(16) DEFAULT 'link' NOT NULL;
+ALTER TABLE public.replications_forum_pairs
+    ADD CONSTRAINT replications_forum_pairs_attachment_fallback_check CHECK (attachment_fallback IN ('link', 'notice'));
@@ -0,0 +1,3 @@
+ALTER TABLE public.replication_message_pairs DROP COLUMN IF EXISTS webhook_id;
+ALTER TABLE public.replications_forum_pairs DROP COLUMN IF EXISTS use_webhook;
+DROP TABLE IF EXISTS public.replication_webhooks;
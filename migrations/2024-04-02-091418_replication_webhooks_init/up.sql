@@ -0,0 +1,17 @@
+DROP TABLE IF EXISTS public.replication_webhooks;
+CREATE TABLE public.replication_webhooks
+(
+    id            bigserial                           NOT NULL,
+    guild_id      bigint                              NOT NULL,
+    channel_id    bigint                              NOT NULL,
+    webhook_id    bigint                              NOT NULL,
+    webhook_token VARCHAR(255)                        NOT NULL,
+    created_at    TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT replication_webhooks_pk PRIMARY KEY (id),
+    CONSTRAINT replication_webhooks_channel_id_key UNIQUE (channel_id)
+);
+
+ALTER TABLE public.replications_forum_pairs
+    ADD use_webhook BOOLEAN DEFAULT FALSE NOT NULL;
+ALTER TABLE public.replication_message_pairs
+    ADD webhook_id int8;
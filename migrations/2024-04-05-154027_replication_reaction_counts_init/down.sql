@@ -0,0 +1,3 @@
+ALTER TABLE public.replications_forum_pairs DROP COLUMN IF EXISTS mirror_reactions;
+DROP INDEX IF EXISTS public.replication_message_pairs_to_idx;
+DROP TABLE IF EXISTS public.replication_reaction_counts;
@@ -0,0 +1,17 @@
+DROP TABLE IF EXISTS public.replication_reaction_counts;
+CREATE TABLE public.replication_reaction_counts
+(
+    id         bigserial                           NOT NULL,
+    channel_id bigint                              NOT NULL,
+    message_id bigint                              NOT NULL,
+    emoji      VARCHAR(255)                        NOT NULL,
+    count      int4                                NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT replication_reaction_counts_pk PRIMARY KEY (id),
+    CONSTRAINT replication_reaction_counts_message_emoji_key UNIQUE (channel_id, message_id, emoji)
+);
+
+CREATE INDEX replication_message_pairs_to_idx ON public.replication_message_pairs (to_channel, to_message);
+
+ALTER TABLE public.replications_forum_pairs
+    ADD mirror_reactions BOOLEAN DEFAULT FALSE NOT NULL;
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS public.replications_reply_target_idx;
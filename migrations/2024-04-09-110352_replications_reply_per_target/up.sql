@@ -0,0 +1 @@
+CREATE UNIQUE INDEX replications_reply_target_idx ON public.replications_reply (channel_id, replication_pairs);
@@ -0,0 +1,2 @@
+ALTER TABLE public.replications_reply DROP COLUMN IF EXISTS reminded_at;
+ALTER TABLE public.replications_forum_pairs DROP COLUMN IF EXISTS on_prompt_expiry;
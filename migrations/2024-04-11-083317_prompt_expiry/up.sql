@@ -0,0 +1,7 @@
+ALTER TABLE public.replications_forum_pairs
+    ADD on_prompt_expiry VARCHAR(16) DEFAULT 'decline' NOT NULL;
+ALTER TABLE public.replications_forum_pairs
+    ADD CONSTRAINT replications_forum_pairs_on_prompt_expiry_check CHECK (on_prompt_expiry IN ('decline', 'accept'));
+
+ALTER TABLE public.replications_reply
+    ADD reminded_at TIMESTAMP;
@@ -0,0 +1,9 @@
+DROP TABLE IF EXISTS public.replication_status_events;
+
+ALTER TABLE public.replications_reply ADD responded BOOLEAN DEFAULT false NOT NULL;
+ALTER TABLE public.replications_reply DROP CONSTRAINT IF EXISTS replications_reply_status_check;
+ALTER TABLE public.replications_reply ALTER COLUMN status DROP DEFAULT;
+UPDATE public.replications_reply
+SET responded = status <> 'pending',
+    status    = CASE WHEN status IN ('accepted', 'paused') THEN 'active' ELSE 'inactive' END;
+ALTER TABLE public.replications_reply ALTER COLUMN status TYPE VARCHAR(255);
@@ -0,0 +1,27 @@
+UPDATE public.replications_reply
+SET status = CASE
+                 WHEN NOT responded THEN 'pending'
+                 WHEN status = 'active' THEN 'accepted'
+                 ELSE 'declined'
+    END;
+
+ALTER TABLE public.replications_reply ALTER COLUMN status TYPE VARCHAR(16);
+ALTER TABLE public.replications_reply ALTER COLUMN status SET DEFAULT 'pending';
+ALTER TABLE public.replications_reply
+    ADD CONSTRAINT replications_reply_status_check CHECK (status IN ('pending', 'accepted', 'declined', 'expired', 'paused', 'revoked'));
+ALTER TABLE public.replications_reply DROP COLUMN responded;
+
+DROP TABLE IF EXISTS public.replication_status_events;
+CREATE TABLE public.replication_status_events
+(
+    id                   bigserial                           NOT NULL,
+    replication_reply_id bigint                              NOT NULL REFERENCES public.replications_reply (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    from_status          VARCHAR(16),
+    to_status            VARCHAR(16)                         NOT NULL,
+    actor_id             bigint,
+    reason               VARCHAR(255),
+    created_at           TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT replication_status_events_pk PRIMARY KEY (id)
+);
+
+CREATE INDEX replication_status_events_reply_idx ON public.replication_status_events (replication_reply_id);
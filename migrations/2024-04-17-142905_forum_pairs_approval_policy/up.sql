@@ -0,0 +1,4 @@
+ALTER TABLE public.replications_forum_pairs
+    ADD approval_policy VARCHAR(16) DEFAULT 'ask_owner' NOT NULL;
+ALTER TABLE public.replications_forum_pairs
+    ADD CONSTRAINT replications_forum_pairs_approval_policy_check CHECK (approval_policy IN ('ask_owner', 'auto_accept', 'auto_decline', 'moderator_only'));
@@ -0,0 +1,7 @@
+DROP TABLE IF EXISTS public.replication_queue;
+DROP TABLE IF EXISTS public.replication_moderation_channels;
+
+UPDATE public.replications_forum_pairs SET approval_policy = 'ask_owner' WHERE approval_policy = 'target_moderators';
+ALTER TABLE public.replications_forum_pairs DROP CONSTRAINT IF EXISTS replications_forum_pairs_approval_policy_check;
+ALTER TABLE public.replications_forum_pairs
+    ADD CONSTRAINT replications_forum_pairs_approval_policy_check CHECK (approval_policy IN ('ask_owner', 'auto_accept', 'auto_decline', 'moderator_only'));
@@ -0,0 +1,29 @@
+ALTER TABLE public.replications_forum_pairs DROP CONSTRAINT IF EXISTS replications_forum_pairs_approval_policy_check;
+ALTER TABLE public.replications_forum_pairs
+    ADD CONSTRAINT replications_forum_pairs_approval_policy_check CHECK (approval_policy IN ('ask_owner', 'auto_accept', 'auto_decline', 'moderator_only', 'target_moderators'));
+
+DROP TABLE IF EXISTS public.replication_moderation_channels;
+CREATE TABLE public.replication_moderation_channels
+(
+    id         bigserial                           NOT NULL,
+    guild_id   bigint                              NOT NULL,
+    channel_id bigint                              NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT replication_moderation_channels_pk PRIMARY KEY (id),
+    CONSTRAINT replication_moderation_channels_guild_key UNIQUE (guild_id)
+);
+
+DROP TABLE IF EXISTS public.replication_queue;
+CREATE TABLE public.replication_queue
+(
+    id                   bigserial                           NOT NULL,
+    replication_reply_id bigint                              NOT NULL REFERENCES public.replications_reply (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    guild_id             bigint                              NOT NULL,
+    channel_id           bigint                              NOT NULL,
+    message_id           bigint,
+    created_at           TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT replication_queue_pk PRIMARY KEY (id),
+    CONSTRAINT replication_queue_reply_key UNIQUE (replication_reply_id)
+);
+
+CREATE INDEX replication_queue_guild_idx ON public.replication_queue (guild_id);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS public.replication_backfill_jobs;
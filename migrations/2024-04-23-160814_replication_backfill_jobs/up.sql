@@ -0,0 +1,17 @@
+DROP TABLE IF EXISTS public.replication_backfill_jobs;
+CREATE TABLE public.replication_backfill_jobs
+(
+    id           bigserial                           NOT NULL,
+    from_guild   bigint                              NOT NULL,
+    from_thread  bigint                              NOT NULL,
+    to_guild     bigint                              NOT NULL,
+    to_thread    bigint                              NOT NULL,
+    last_message bigint,
+    status       VARCHAR(16) DEFAULT 'running'       NOT NULL,
+    error        VARCHAR(255),
+    created_at   TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT replication_backfill_jobs_pk PRIMARY KEY (id),
+    CONSTRAINT replication_backfill_jobs_status_check CHECK (status IN ('running', 'done', 'failed'))
+);
+
+CREATE INDEX replication_backfill_jobs_threads_idx ON public.replication_backfill_jobs (from_thread, to_thread);
@@ -0,0 +1,4 @@
+ALTER TABLE public.replications_forum_pairs
+    ADD thread_sync VARCHAR(16) DEFAULT 'mirror' NOT NULL;
+ALTER TABLE public.replications_forum_pairs
+    ADD CONSTRAINT replications_forum_pairs_thread_sync_check CHECK (thread_sync IN ('mirror', 'annotate', 'off'));
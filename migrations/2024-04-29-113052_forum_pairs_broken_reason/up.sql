@@ -0,0 +1,2 @@
+ALTER TABLE public.replications_forum_pairs
+    ADD broken_reason VARCHAR(255);
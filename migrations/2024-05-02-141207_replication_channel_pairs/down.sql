@@ -0,0 +1 @@
+DROP TABLE IF EXISTS public.replication_channel_pairs;
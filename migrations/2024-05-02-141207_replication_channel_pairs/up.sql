@@ -0,0 +1,12 @@
+DROP TABLE IF EXISTS public.replication_channel_pairs;
+CREATE TABLE public.replication_channel_pairs
+(
+    id           bigserial                           NOT NULL,
+    from_guild   bigint                              NOT NULL,
+    from_channel bigint                              NOT NULL,
+    to_guild     bigint                              NOT NULL,
+    to_channel   bigint                              NOT NULL,
+    created_at   TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT replication_channel_pairs_pk PRIMARY KEY (id),
+    CONSTRAINT replication_channel_pairs_channels_key UNIQUE (from_channel, to_channel)
+);
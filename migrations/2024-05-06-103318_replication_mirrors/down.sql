@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS public.replication_mirror_channels;
+DROP TABLE IF EXISTS public.replication_mirrors;
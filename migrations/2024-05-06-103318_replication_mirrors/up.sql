@@ -0,0 +1,24 @@
+DROP TABLE IF EXISTS public.replication_mirrors;
+CREATE TABLE public.replication_mirrors
+(
+    id            bigserial                           NOT NULL,
+    from_guild    bigint                              NOT NULL,
+    from_category bigint,
+    to_guild      bigint                              NOT NULL,
+    created_at    TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT replication_mirrors_pk PRIMARY KEY (id)
+);
+
+CREATE INDEX replication_mirrors_from_guild_idx ON public.replication_mirrors (from_guild);
+
+DROP TABLE IF EXISTS public.replication_mirror_channels;
+CREATE TABLE public.replication_mirror_channels
+(
+    id           bigserial                           NOT NULL,
+    mirror_id    bigint                              NOT NULL REFERENCES public.replication_mirrors (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    from_channel bigint                              NOT NULL,
+    to_channel   bigint                              NOT NULL,
+    created_at   TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT replication_mirror_channels_pk PRIMARY KEY (id),
+    CONSTRAINT replication_mirror_channels_from_key UNIQUE (mirror_id, from_channel)
+);
@@ -0,0 +1,5 @@
+ALTER TABLE public.replications_forum_pairs DROP COLUMN IF EXISTS origin_label;
+ALTER TABLE public.replications_forum_pairs
+    ADD origin_label VARCHAR(16) DEFAULT 'off' NOT NULL;
+ALTER TABLE public.replications_forum_pairs
+    ADD CONSTRAINT replications_forum_pairs_origin_label_check CHECK (origin_label IN ('off', 'tag', 'prefix'));
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS public.replication_message_pairs_origin_idx;
+ALTER TABLE public.replication_message_pairs
+    DROP COLUMN IF EXISTS origin_guild,
+    DROP COLUMN IF EXISTS origin_channel,
+    DROP COLUMN IF EXISTS origin_message,
+    DROP COLUMN IF EXISTS hops;
@@ -0,0 +1,18 @@
+ALTER TABLE public.replication_message_pairs
+    ADD origin_guild   bigint,
+    ADD origin_channel bigint,
+    ADD origin_message bigint,
+    ADD hops           int DEFAULT 1 NOT NULL;
+
+-- Copies made so far were all made straight from their source
+UPDATE public.replication_message_pairs
+SET origin_guild   = from_guild,
+    origin_channel = from_channel,
+    origin_message = from_message;
+
+ALTER TABLE public.replication_message_pairs
+    ALTER COLUMN origin_guild SET NOT NULL,
+    ALTER COLUMN origin_channel SET NOT NULL,
+    ALTER COLUMN origin_message SET NOT NULL;
+
+CREATE INDEX replication_message_pairs_origin_idx ON public.replication_message_pairs (origin_channel, origin_message);
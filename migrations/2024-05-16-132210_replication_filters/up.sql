@@ -0,0 +1,12 @@
+DROP TABLE IF EXISTS public.replication_filters;
+CREATE TABLE public.replication_filters
+(
+    id         bigserial                           NOT NULL,
+    pair_id    bigint                              NOT NULL REFERENCES public.replications_forum_pairs (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    kind       VARCHAR(16)                         NOT NULL,
+    value      VARCHAR(255)                        NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT replication_filters_pk PRIMARY KEY (id),
+    CONSTRAINT replication_filters_rule_key UNIQUE (pair_id, kind, value),
+    CONSTRAINT replication_filters_kind_check CHECK (kind IN ('regex', 'word', 'prefix', 'allow_author', 'deny_author', 'allow_role', 'deny_role'))
+);
@@ -0,0 +1,2 @@
+ALTER TABLE public.replications_forum_pairs
+    ALTER COLUMN thread_sync SET DEFAULT 'mirror';
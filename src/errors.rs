@@ -38,9 +38,19 @@ impl AppError {
                     diesel::result::DatabaseErrorKind::UniqueViolation,
                     _,
                 ) => ErrorType::BadRequest,
+                diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::ForeignKeyViolation,
+                    _,
+                ) => ErrorType::BadRequest,
                 diesel::result::Error::NotFound => ErrorType::NotFound,
                 _ => ErrorType::Internal,
             },
         )
     }
 }
+
+impl From<diesel::result::Error> for AppError {
+    fn from(err: diesel::result::Error) -> AppError {
+        AppError::from_diesel_err(err, "while running a transaction")
+    }
+}
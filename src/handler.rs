@@ -1,20 +1,50 @@
-use serenity::all::{GuildId, PartialGuildChannel};
+use serenity::all::{GuildId, PartialGuildChannel, Webhook};
 use std::any::Any;
+use regex::Regex;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use serenity::all::{CacheHttp, ChannelId, ChannelType, Context, EventHandler, GuildChannel, Message, MessageId, Reaction, Ready};
 use serenity::async_trait;
+use serenity::prelude::TypeMapKey;
 use tokio::runtime::Handle;
 use crate::database::{DBAccessManager, PgPool};
 use crate::DbHandler;
 use crate::errors::{AppError, ErrorType};
 use crate::log::{write_error_log, write_info_log};
 
+mod attachments;
+mod backfill;
+mod broken;
 pub mod commands;
+mod content;
+mod filters;
 pub mod handlers;
 pub mod hooks;
+mod lifecycle;
+mod mirrors;
+mod moderation;
 mod owner;
+mod prompts;
+mod reactions;
+mod replication;
+mod scheduler;
+mod slash_commands;
+mod validation;
 mod db_access;
+mod webhooks;
 
+#[derive(Clone)]
 pub struct Handler {
     pub pool: Arc<DbHandler>,
+    // Replication webhooks by the forum channel they post in
+    pub webhooks: Arc<tokio::sync::Mutex<HashMap<u64, Webhook>>>,
+    // Set once the background tasks run, `ready` fires again on every reconnect
+    pub scheduler_started: Arc<AtomicBool>,
+    // Compiled `regex` filters by pattern, `None` for a pattern that no longer compiles
+    pub filter_patterns: Arc<std::sync::Mutex<HashMap<String, Option<Regex>>>>,
+}
+
+impl TypeMapKey for Handler {
+    type Value = Handler;
 }
@@ -0,0 +1,107 @@
+use serenity::all::{Attachment, CreateAttachment, PremiumTier};
+use crate::handler::db_access::AttachmentFallback;
+use crate::log::{write_error_log, write_info_log};
+
+const MEBIBYTE: u64 = 1024 * 1024;
+
+// Discord rejects messages longer than this, in characters.
+const MAX_CONTENT_LENGTH: usize = 2000;
+
+// Upload limit of a guild, boosts raise it from the default 10 MiB.
+pub fn upload_limit(tier: PremiumTier) -> u64 {
+    match tier {
+        PremiumTier::Tier2 => 50 * MEBIBYTE,
+        PremiumTier::Tier3 => 100 * MEBIBYTE,
+        _ => 10 * MEBIBYTE,
+    }
+}
+
+fn human_size(size: u64) -> String {
+    if size >= MEBIBYTE {
+        format!("{:.1} MiB", size as f64 / MEBIBYTE as f64)
+    } else {
+        format!("{:.1} KiB", size as f64 / 1024.0)
+    }
+}
+
+fn fallback_line(attachment: &Attachment, fallback: AttachmentFallback) -> String {
+    match fallback {
+        AttachmentFallback::Link => format!("📎 [{}]({}) ({}, too large to re-upload)", attachment.filename, attachment.url, human_size(attachment.size as u64)),
+        AttachmentFallback::Notice => format!("📎 `{}` ({}) was too large to replicate", attachment.filename, human_size(attachment.size as u64)),
+    }
+}
+
+// Whether each attachment fits in `limit` bytes, counting the ones before it that fit.
+fn fitting(attachments: &[Attachment], limit: u64) -> Vec<bool> {
+    let mut total: u64 = 0;
+    attachments.iter()
+        .map(|attachment| {
+            let fits = total + attachment.size as u64 <= limit;
+            if fits {
+                total += attachment.size as u64;
+            }
+            fits
+        })
+        .collect()
+}
+
+/// Downloads the attachments that fit in `limit` bytes, in total, so they can be re-uploaded.
+/// Anything that does not fit or fails to download is returned as a fallback line for the content.
+pub async fn replicate_attachments(attachments: &[Attachment], limit: u64, fallback: AttachmentFallback) -> (Vec<CreateAttachment>, Vec<String>) {
+    let mut files = Vec::new();
+    let mut fallback_lines = Vec::new();
+
+    for (attachment, fits) in attachments.iter().zip(fitting(attachments, limit)) {
+        if !fits {
+            write_info_log(format!("Attachment {} ({} bytes) over upload limit {}", attachment.filename, attachment.size, limit));
+            fallback_lines.push(fallback_line(attachment, fallback));
+            continue;
+        }
+
+        match attachment.download().await {
+            Ok(data) => {
+                files.push(CreateAttachment::bytes(data, attachment.filename.clone()));
+            }
+            Err(why) => {
+                write_error_log(format!("Error downloading attachment {}: {why:?}", attachment.filename));
+                fallback_lines.push(fallback_line(attachment, fallback));
+            }
+        }
+    }
+
+    (files, fallback_lines)
+}
+
+/// The fallback lines of the attachments over `limit`, without downloading anything, for edits of
+/// a copy. Attachments that failed to download when the copy was sent are not known any more.
+pub fn oversized_lines(attachments: &[Attachment], limit: u64, fallback: AttachmentFallback) -> Vec<String> {
+    attachments.iter()
+        .zip(fitting(attachments, limit))
+        .filter(|(_, fits)| !fits)
+        .map(|(attachment, _)| fallback_line(attachment, fallback))
+        .collect()
+}
+
+/// Appends the fallback lines to `body` and shortens the body so that the message stays within
+/// Discord's length limit. Fallback lines are kept whole as long as they fit on their own.
+pub fn with_fallback_lines(body: &str, fallback_lines: &[String]) -> String {
+    let mut lines = String::new();
+    for line in fallback_lines {
+        if lines.chars().count() + line.chars().count() + 1 >= MAX_CONTENT_LENGTH {
+            break;
+        }
+        lines.push('\n');
+        lines.push_str(line);
+    }
+
+    let room = MAX_CONTENT_LENGTH - lines.chars().count();
+    let body = if body.chars().count() > room {
+        let mut shortened: String = body.chars().take(room - 1).collect();
+        shortened.push('…');
+        shortened
+    } else {
+        body.to_string()
+    };
+
+    format!("{}{}", body, lines)
+}
@@ -0,0 +1,110 @@
+use serenity::all::{ChannelId, Context, GetMessages, MessageId, MessageType};
+use crate::errors::{AppError, ErrorType};
+use crate::handler::db_access::{ReplicationBackfillJob, ReplicationBackfillJobData};
+use crate::handler::Handler;
+use crate::log::{write_error_log, write_info_log};
+
+const PAGE_SIZE: u8 = 100;
+// Length of `replication_backfill_jobs.error`
+const ERROR_LENGTH: usize = 255;
+
+impl Handler {
+    /// Starts replaying the history of `dto.from_thread` into `dto.to_thread` in the background,
+    /// unless a backfill between both threads is already running. Returns the job and whether it
+    /// was started by this call.
+    pub(crate) fn start_backfill(&self, ctx: &Context, dto: ReplicationBackfillJobData) -> Result<(ReplicationBackfillJob, bool), AppError> {
+        let db = self.get_access()?;
+
+        if let Some(job) = db.get_running_replication_backfill_job_for_threads(dto.from_thread, dto.to_thread)? {
+            return Ok((job, false));
+        }
+
+        let job = db.create_replication_backfill_job(dto)?;
+        self.spawn_backfill(ctx, job.clone());
+
+        Ok((job, true))
+    }
+
+    /// Picks up the jobs left running when the bot stopped, from the last message they replayed.
+    pub(crate) fn resume_backfills(&self, ctx: &Context) {
+        match self.get_access().and_then(|db| db.get_running_replication_backfill_jobs()) {
+            Ok(jobs) => {
+                for job in jobs {
+                    write_info_log(format!("Resuming backfill {} of thread {}", job.id, job.from_thread));
+                    self.spawn_backfill(ctx, job);
+                }
+            }
+            Err(err) => write_error_log(format!("Error getting running backfills: {}", err.message)),
+        }
+    }
+
+    fn spawn_backfill(&self, ctx: &Context, job: ReplicationBackfillJob) {
+        let handler = self.clone();
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            let result = handler.replay_history(&ctx, &job).await;
+
+            let error = match result {
+                Ok(replayed) => {
+                    write_info_log(format!("Backfill {} done: {} messages replayed into {}", job.id, replayed, job.to_thread));
+                    None
+                }
+                Err(err) => {
+                    write_error_log(format!("Backfill {} failed: {}", job.id, err.message));
+                    Some(err.message.chars().take(ERROR_LENGTH).collect())
+                }
+            };
+
+            if let Err(err) = handler.get_access().and_then(|db| db.finish_replication_backfill_job(job.id, error)) {
+                write_error_log(format!("Error updating backfill {}: {}", job.id, err.message));
+            }
+        });
+    }
+
+    // Pages through the source thread oldest first, after the job's cursor, and replicates every
+    // message that passes the pair's filters and has no copy in the target thread yet. The cursor
+    // moves after each message so an interrupted job resumes where it stopped.
+    async fn replay_history(&self, ctx: &Context, job: &ReplicationBackfillJob) -> Result<usize, AppError> {
+        let thread_pair = self.get_access()?
+            .get_replication_thread_pairs(job.from_guild, job.from_thread)?
+            .into_iter()
+            .find(|f| f.to_thread == job.to_thread)
+            .ok_or_else(|| AppError::new(format!("Thread {} is no longer replicated to {}", job.from_thread, job.to_thread).as_str(), ErrorType::NotFound))?;
+
+        let pair_id = self.get_access()?.get_replication_forum_pair_for_threads(job.from_thread, job.to_thread).ok().map(|pair| pair.id);
+
+        let source = ChannelId::new(job.from_thread as u64);
+        // The starter message shares the thread id and is copied when the remote post is created
+        let mut cursor = MessageId::new(job.last_message.unwrap_or(job.from_thread) as u64);
+        let mut replayed = 0;
+
+        loop {
+            let mut page = source.messages(&ctx.http, GetMessages::new().after(cursor).limit(PAGE_SIZE)).await
+                .map_err(|why| AppError::new(format!("Error reading thread history: {why}").as_str(), ErrorType::DistantServer))?;
+            if page.is_empty() {
+                break;
+            }
+            page.sort_by_key(|msg| msg.id);
+
+            for msg in page {
+                let replicable = !self.produced_by_bot(ctx, &msg)
+                    && matches!(msg.kind, MessageType::Regular | MessageType::InlineReply)
+                    && pair_id.is_none_or(|pair_id| self.filtered(ctx, job.from_guild, &msg, pair_id).is_none());
+                let copied = self.get_access()?
+                    .get_replicated_counterpart(job.from_thread, msg.id.get() as i64, job.to_thread)
+                    .is_ok();
+
+                if replicable && !copied {
+                    let copy = self.replicate_message(ctx, &msg, &thread_pair).await?;
+                    self.forward_copies(ctx, vec![(job.to_guild, copy)]).await;
+                    replayed += 1;
+                }
+
+                cursor = msg.id;
+                self.get_access()?.update_replication_backfill_cursor(job.id, cursor.get() as i64)?;
+            }
+        }
+
+        Ok(replayed)
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use serenity::all::{ChannelId, Context, CreateMessage, GuildId};
+use crate::handler::content::no_mentions;
+use crate::handler::db_access::ReplicationForumPair;
+use crate::handler::slash_commands::describe_pair;
+use crate::handler::Handler;
+use crate::log::{write_error_log, write_info_log};
+
+impl Handler {
+    /// Marks the pairs replicating from or to the deleted forum as broken.
+    pub(crate) async fn forum_deleted(&self, ctx: &Context, guild_id: GuildId, forum_id: ChannelId, name: &str) {
+        let forum_id = forum_id.get() as i64;
+        let reason = format!("Forum #{} ({}) was deleted", name, forum_id);
+
+        let broken = match self.get_access().and_then(|db| db.mark_replication_forum_pairs_broken_by_forum(forum_id, reason.clone())) {
+            Ok(broken) => broken,
+            Err(err) => {
+                write_error_log(format!("Error marking pairs of forum {} as broken: {}", forum_id, err.message));
+                return;
+            }
+        };
+
+        // The side that kept its forum is told
+        let surviving = |pair: &ReplicationForumPair| if pair.from_forum == forum_id { pair.to_guild } else { pair.from_guild };
+        self.notify_broken(ctx, &broken, surviving, &reason).await;
+        write_info_log(format!("Forum {} deleted in guild {}, {} pair(s) broken", forum_id, guild_id, broken.len()));
+    }
+
+    /// Marks the pairs with a side in a guild the bot was removed from as broken.
+    pub(crate) async fn guild_removed(&self, ctx: &Context, guild_id: GuildId) {
+        let guild_id = guild_id.get() as i64;
+        let reason = format!("The bot was removed from server {}", guild_id);
+
+        let broken = match self.get_access().and_then(|db| db.mark_replication_forum_pairs_broken_by_guild(guild_id, reason.clone())) {
+            Ok(broken) => broken,
+            Err(err) => {
+                write_error_log(format!("Error marking pairs of guild {} as broken: {}", guild_id, err.message));
+                return;
+            }
+        };
+
+        let surviving = |pair: &ReplicationForumPair| if pair.from_guild == guild_id { pair.to_guild } else { pair.from_guild };
+        let reachable: Vec<ReplicationForumPair> = broken.into_iter().filter(|pair| surviving(pair) != guild_id).collect();
+        self.notify_broken(ctx, &reachable, surviving, &reason).await;
+        write_info_log(format!("Removed from guild {}, {} pair(s) broken", guild_id, reachable.len()));
+    }
+
+    // One message per surviving guild listing its broken pairs.
+    async fn notify_broken(&self, ctx: &Context, broken: &[ReplicationForumPair], surviving: impl Fn(&ReplicationForumPair) -> i64, reason: &str) {
+        let mut by_guild: HashMap<i64, Vec<String>> = HashMap::new();
+        for pair in broken {
+            by_guild.entry(surviving(pair)).or_default().push(describe_pair(&ctx.cache, pair));
+        }
+
+        for (guild_id, pairs) in by_guild {
+            let content = format!(
+                "Replication paused: {}\n{}\nUse `!broken` to review, `!repair <pair_id>` to fix or `!unlink <pair_id>` to remove.",
+                reason,
+                pairs.join("\n"),
+            );
+            notify_admins(ctx, guild_id, content).await;
+        }
+    }
+}
+
+// Posts in the system channel of the guild, or DMs its owner when there is none or it fails.
+async fn notify_admins(ctx: &Context, guild_id: i64, content: String) {
+    let (system_channel, owner) = match ctx.cache.guild(guild_id as u64) {
+        Some(guild) => (guild.system_channel_id, guild.owner_id),
+        None => {
+            write_error_log(format!("Cannot notify guild {}: not in cache", guild_id));
+            return;
+        }
+    };
+
+    if let Some(channel) = system_channel {
+        match channel.send_message(&ctx.http, CreateMessage::new().content(&content).allowed_mentions(no_mentions())).await {
+            Ok(_) => return,
+            Err(why) => write_error_log(format!("Error notifying guild {} in its system channel: {why:?}", guild_id)),
+        }
+    }
+
+    if let Err(why) = owner.direct_message(&ctx.http, CreateMessage::new().content(content)).await {
+        write_error_log(format!("Error notifying the owner of guild {}: {why:?}", guild_id));
+    }
+}
@@ -5,7 +5,7 @@ use std::collections::HashSet;
 use serenity::framework::standard::buckets::{LimitedFor, RevertBucket};
 use std::sync::Arc;
 use serenity::framework::standard::macros::{check, command, group, help, hook};
-use serenity::all::{Context, Message, UserId};
+use serenity::all::{ChannelId, ChannelType, Context, CreateMessage, Message, UserId};
 use serenity::framework::standard::{
     help_commands,
     Args,
@@ -23,8 +23,16 @@ use serenity::gateway::ShardManager;
 use serenity::prelude::TypeMapKey;
 use crate::{DbHandler, handle_database_init};
 use crate::database::DBAccessManager;
-use crate::handler::db_access::ReplicationForumPairData;
+use crate::handler::db_access::{ApprovalPolicy, AttachmentFallback, DeletePolicy, ExpiryPolicy, FilterKind, OriginLabel, ThreadSync, ReplicationBackfillJobData, ReplicationChannelPairData, ReplicationFilterData, ReplicationForumPairData, ReplicationMirrorData, ReplicationModerationChannelData, ReplicationStatus};
+use crate::handler::Handler;
+use crate::handler::content::no_mentions;
+use crate::handler::filters::validate_filter;
+use crate::handler::mirrors::describe_mirror;
+use crate::handler::moderation::queue_description;
+use crate::handler::slash_commands::{describe_channel_pair, describe_pair};
+use crate::handler::validation::{validate_channel_pair, validate_forum_pair, validate_mirror};
 use crate::handler::hooks::{after, before, unknown_command};
+use crate::errors::ErrorType;
 use crate::log::write_info_log;
 
 struct ShardManagerContainer;
@@ -35,7 +43,7 @@ impl TypeMapKey for ShardManagerContainer {
 
 
 #[group]
-#[commands(about, am_i_admin, ping, latency, link)]
+#[commands(about, am_i_admin, ping, latency, link, pairs, unlink, relink, channel_link, channel_unlink, mirror, mirrors, mirror_sync, unmirror, filters, filter_add, filter_remove, pair_set, broken, repair, mod_channel, queue, queue_approve, queue_reject, backfill, pause, resume)]
 pub struct Commands;
 
 // The framework provides two built-in help commands for you to use. But you can also make your own
@@ -185,55 +193,891 @@ async fn about(ctx: &Context, msg: &Message) -> CommandResult {
 #[command]
 async fn link(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     let all_args = args.rest();
-    let args: Vec<&str> = all_args.split(" ").collect();
+    let args: Vec<&str> = all_args.split_whitespace().collect();
     if args.len() == 4 {
-        let from_guild = args[0].parse::<i64>();
-        let from_forum = args[1].parse::<i64>();
-        let to_guild = args[2].parse::<i64>();
-        let to_forum = args[3].parse::<i64>();
+        let mut ids = Vec::with_capacity(4);
+        for (arg, name) in args.iter().zip(["from_guild_id", "from_channel_id", "to_guild_id", "to_channel_id"]) {
+            match arg.parse::<i64>() {
+                Ok(id) => ids.push(id),
+                Err(_) => {
+                    msg.reply(ctx, &format!("`{}` is not a valid {}", arg, name)).await?;
+                    return Ok(());
+                }
+            }
+        }
+        let (from_guild, from_forum, to_guild, to_forum) = (ids[0], ids[1], ids[2], ids[3]);
+
+        if let Err(e) = validate_forum_pair(ctx, msg.author.id, from_guild, from_forum, to_guild, to_forum).await {
+            msg.reply(ctx, &format!("Cannot create replication pair: {}", e.message)).await?;
+            return Ok(());
+        }
+
+        let data = ctx.data.read().await;
+
+        let db_access_pool = match data.get::<DbHandler>() {
+            Some(v) => v,
+            None => {
+                msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+                return Ok(());
+            }
+        };
+
+        let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+
+        let to_insert = ReplicationForumPairData {
+            from_guild,
+            from_forum,
+            to_guild,
+            to_forum,
+        };
+        match _db_access.create_replication_forum_pair(to_insert) {
+            Ok(created) => {
+                msg.reply(ctx, &format!("Replication pair created: {}", describe_pair(&ctx.cache, &created))).await?;
+                write_info_log(format!("Replication pair created {:?}", created));
+            }
+            Err(e) => {
+                msg.reply(ctx, &format!("Error creating replication pair: {}", e.message)).await?;
+            }
+        }
+    } else {
+        msg.channel_id.say(&ctx.http, "Invalid arguments from_guild_id from_channel_id to_guild_id to_channel_id").await?;
+    }
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[description = "List the replication pairs of this server"]
+async fn pairs(ctx: &Context, msg: &Message) -> CommandResult {
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+
+    let guild_id = msg.guild_id.unwrap_or_default().get() as i64;
+    let forums = _db_access.get_replication_forum_pairs_by_guild(guild_id);
+    let channels = _db_access.get_replication_channel_pairs_by_guild(guild_id);
+
+    let content = match (forums, channels) {
+        (Ok(forums), Ok(channels)) if forums.is_empty() && channels.is_empty() => "No replication pair in this server".to_string(),
+        (Ok(forums), Ok(channels)) => forums.iter().map(|p| describe_pair(&ctx.cache, p))
+            .chain(channels.iter().map(|p| describe_channel_pair(&ctx.cache, p)))
+            .collect::<Vec<String>>()
+            .join("\n"),
+        (Err(e), _) | (_, Err(e)) => format!("Error listing replication pairs: {}", e.message),
+    };
+
+    msg.channel_id.say(&ctx.http, content).await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[usage = "<pair_id> [cascade]"]
+#[description = "Remove a replication pair of this server. With `cascade`, its replicated threads are dropped too."]
+async fn unlink(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let args: Vec<&str> = args.rest().split_whitespace().collect();
+    let pair_id = match args.first().map(|a| a.parse::<i64>()) {
+        Some(Ok(pair_id)) => pair_id,
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments pair_id [cascade]").await?;
+            return Ok(());
+        }
+    };
+    let cascade = args.get(1).is_some_and(|a| *a == "cascade");
+
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+    let guild_id = msg.guild_id.unwrap_or_default().get() as i64;
+
+    let pair = match _db_access.get_replication_forum_pair_by_id(pair_id) {
+        Ok(pair) if pair.from_guild == guild_id || pair.to_guild == guild_id => pair,
+        _ => {
+            msg.reply(ctx, &format!("No replication pair {} in this server", pair_id)).await?;
+            return Ok(());
+        }
+    };
+
+    match _db_access.delete_replication_forum_pair(pair.id, cascade) {
+        Ok(_) => {
+            msg.reply(ctx, &format!("Replication pair removed: {}", describe_pair(&ctx.cache, &pair))).await?;
+            write_info_log(format!("Replication pair removed {:?}", pair));
+        }
+        Err(e) if matches!(e.err_type, ErrorType::BadRequest) => {
+            msg.reply(ctx, &format!("Replication pair {} still has replicated threads, use `unlink {} cascade`", pair.id, pair.id)).await?;
+        }
+        Err(e) => {
+            msg.reply(ctx, &format!("Error removing replication pair: {}", e.message)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[usage = "<pair_id> <to_guild_id> <to_forum_id>"]
+#[description = "Point a replication pair created from this server to another forum"]
+async fn relink(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let args: Vec<&str> = args.rest().split_whitespace().collect();
+    if args.len() != 3 {
+        msg.channel_id.say(&ctx.http, "Invalid arguments pair_id to_guild_id to_forum_id").await?;
+        return Ok(());
+    }
+    let (pair_id, to_guild, to_forum) = match (args[0].parse::<i64>(), args[1].parse::<i64>(), args[2].parse::<i64>()) {
+        (Ok(pair_id), Ok(to_guild), Ok(to_forum)) => (pair_id, to_guild, to_forum),
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments pair_id to_guild_id to_forum_id").await?;
+            return Ok(());
+        }
+    };
+
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+
+    let pair = match _db_access.get_replication_forum_pair_by_id(pair_id) {
+        Ok(pair) if pair.from_guild == msg.guild_id.unwrap_or_default().get() as i64 => pair,
+        _ => {
+            msg.reply(ctx, &format!("No replication pair {} from this server", pair_id)).await?;
+            return Ok(());
+        }
+    };
+
+    if let Err(e) = validate_forum_pair(ctx, msg.author.id, pair.from_guild, pair.from_forum, to_guild, to_forum).await {
+        msg.reply(ctx, &format!("Cannot update replication pair: {}", e.message)).await?;
+        return Ok(());
+    }
+
+    let to_update = ReplicationForumPairData {
+        from_guild: pair.from_guild,
+        from_forum: pair.from_forum,
+        to_guild,
+        to_forum,
+    };
+    // A relinked pair points at a forum that was just validated
+    let updated = _db_access.update_replication_forum_pair(pair.id, to_update)
+        .and_then(|updated| match updated.broken_reason {
+            Some(_) => _db_access.update_replication_forum_pair_broken_reason(updated.id, None),
+            None => Ok(updated),
+        });
+    match updated {
+        Ok(updated) => {
+            msg.reply(ctx, &format!("Replication pair updated: {}", describe_pair(&ctx.cache, &updated))).await?;
+            write_info_log(format!("Replication pair updated {:?}", updated));
+        }
+        Err(e) => {
+            msg.reply(ctx, &format!("Error updating replication pair: {}", e.message)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[usage = "<to_guild_id> <to_channel_id> [both]"]
+#[description = "Mirror the messages of this text channel into a channel of another server. With `both`, messages are mirrored back too."]
+async fn channel_link(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let args: Vec<&str> = args.rest().split_whitespace().collect();
+    let (to_guild, to_channel) = match (args.first().map(|a| a.parse::<i64>()), args.get(1).map(|a| a.parse::<i64>())) {
+        (Some(Ok(to_guild)), Some(Ok(to_channel))) => (to_guild, to_channel),
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments to_guild_id to_channel_id [both]").await?;
+            return Ok(());
+        }
+    };
+    let both = args.get(2).is_some_and(|a| *a == "both");
+    let from_guild = msg.guild_id.unwrap_or_default().get() as i64;
+    let from_channel = msg.channel_id.get() as i64;
+
+    if let Err(e) = validate_channel_pair(ctx, msg.author.id, from_guild, from_channel, to_guild, to_channel).await {
+        msg.reply(ctx, &format!("Cannot create channel pair: {}", e.message)).await?;
+        return Ok(());
+    }
+    if both {
+        if let Err(e) = validate_channel_pair(ctx, msg.author.id, to_guild, to_channel, from_guild, from_channel).await {
+            msg.reply(ctx, &format!("Cannot create channel pair: {}", e.message)).await?;
+            return Ok(());
+        }
+    }
+
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+
+    let mut to_insert = vec![ReplicationChannelPairData { from_guild, from_channel, to_guild, to_channel }];
+    if both {
+        to_insert.push(ReplicationChannelPairData { from_guild: to_guild, from_channel: to_channel, to_guild: from_guild, to_channel: from_channel });
+    }
+
+    for dto in to_insert {
+        match _db_access.create_replication_channel_pair(dto) {
+            Ok(created) => {
+                msg.reply(ctx, &format!("Channel pair created: {}", describe_channel_pair(&ctx.cache, &created))).await?;
+                write_info_log(format!("Channel pair created {:?}", created));
+            }
+            Err(e) => {
+                msg.reply(ctx, &format!("Error creating channel pair: {}", e.message)).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[usage = "<pair_id>"]
+#[description = "Stop mirroring a text channel pair of this server"]
+async fn channel_unlink(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let pair_id = match args.rest().split_whitespace().next().map(|a| a.parse::<i64>()) {
+        Some(Ok(pair_id)) => pair_id,
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments pair_id").await?;
+            return Ok(());
+        }
+    };
+
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+    let guild_id = msg.guild_id.unwrap_or_default().get() as i64;
+
+    let pair = match _db_access.get_replication_channel_pair_by_id(pair_id) {
+        Ok(pair) if pair.from_guild == guild_id || pair.to_guild == guild_id => pair,
+        _ => {
+            msg.reply(ctx, &format!("No channel pair {} in this server", pair_id)).await?;
+            return Ok(());
+        }
+    };
+
+    match _db_access.delete_replication_channel_pair(pair.id) {
+        Ok(_) => {
+            msg.reply(ctx, &format!("Channel pair removed: {}", describe_channel_pair(&ctx.cache, &pair))).await?;
+            write_info_log(format!("Channel pair removed {:?}", pair));
+        }
+        Err(e) => {
+            msg.reply(ctx, &format!("Error removing channel pair: {}", e.message)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[usage = "<to_guild_id> [category_id]"]
+#[description = "Mirror a category of this server, or the whole server, into another server. Missing channels and forums are created there and paired, channels created later are picked up. Channels hidden from @everyone are left out."]
+async fn mirror(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let args: Vec<&str> = args.rest().split_whitespace().collect();
+    let (to_guild, from_category) = match (args.first().map(|a| a.parse::<i64>()), args.get(1).map(|a| a.parse::<i64>())) {
+        (Some(Ok(to_guild)), None) => (to_guild, None),
+        (Some(Ok(to_guild)), Some(Ok(category))) => (to_guild, Some(category)),
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments to_guild_id [category_id]").await?;
+            return Ok(());
+        }
+    };
+    let from_guild = msg.guild_id.unwrap_or_default().get() as i64;
+
+    if let Err(e) = validate_mirror(ctx, msg.author.id, from_guild, from_category, to_guild).await {
+        msg.reply(ctx, &format!("Cannot create mirror: {}", e.message)).await?;
+        return Ok(());
+    }
+
+    let handler = {
+        let data = ctx.data.read().await;
+
+        match data.get::<Handler>() {
+            Some(v) => v.clone(),
+            None => {
+                msg.reply(ctx, "There was a problem getting the replication handler").await?;
+
+                return Ok(());
+            }
+        }
+    };
+
+    let created = handler.get_access().and_then(|db| db.create_replication_mirror(ReplicationMirrorData {
+        from_guild,
+        from_category,
+        to_guild,
+    }));
+    let created = match created {
+        Ok(created) => created,
+        Err(e) => {
+            msg.reply(ctx, &format!("Error creating mirror: {}", e.message)).await?;
+            return Ok(());
+        }
+    };
+    write_info_log(format!("Mirror created {:?}", created));
+
+    let content = match handler.sync_mirror(ctx, &created).await {
+        Ok(mirrored) => format!("Mirror created: {}\n{} channel(s) mirrored", describe_mirror(&ctx.cache, &created), mirrored),
+        Err(e) => format!("Mirror created: {}\nError mirroring channels: {}", describe_mirror(&ctx.cache, &created), e.message),
+    };
+    msg.reply(ctx, &content).await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[description = "List the mirrors of this server"]
+async fn mirrors(ctx: &Context, msg: &Message) -> CommandResult {
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+
+    let content = match _db_access.get_replication_mirrors_by_guild(msg.guild_id.unwrap_or_default().get() as i64) {
+        Ok(mirrors) if mirrors.is_empty() => "No mirror in this server".to_string(),
+        Ok(mirrors) => mirrors.iter().map(|m| describe_mirror(&ctx.cache, m)).collect::<Vec<String>>().join("\n"),
+        Err(e) => format!("Error listing mirrors: {}", e.message),
+    };
+
+    msg.channel_id.say(&ctx.http, content).await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[usage = "<mirror_id>"]
+#[description = "Mirror the channels a mirror of this server is still missing, for instance after an error"]
+async fn mirror_sync(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mirror_id = match args.rest().split_whitespace().next().map(|a| a.parse::<i64>()) {
+        Some(Ok(mirror_id)) => mirror_id,
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments mirror_id").await?;
+            return Ok(());
+        }
+    };
+    let guild_id = msg.guild_id.unwrap_or_default().get() as i64;
+
+    let handler = {
+        let data = ctx.data.read().await;
+
+        match data.get::<Handler>() {
+            Some(v) => v.clone(),
+            None => {
+                msg.reply(ctx, "There was a problem getting the replication handler").await?;
+
+                return Ok(());
+            }
+        }
+    };
+
+    let mirror = match handler.get_access().and_then(|db| db.get_replication_mirror_by_id(mirror_id)) {
+        Ok(mirror) if mirror.from_guild == guild_id || mirror.to_guild == guild_id => mirror,
+        _ => {
+            msg.reply(ctx, &format!("No mirror {} in this server", mirror_id)).await?;
+            return Ok(());
+        }
+    };
+
+    let content = match handler.sync_mirror(ctx, &mirror).await {
+        Ok(mirrored) => format!("{}\n{} channel(s) mirrored", describe_mirror(&ctx.cache, &mirror), mirrored),
+        Err(e) => format!("Error mirroring channels: {}", e.message),
+    };
+    msg.reply(ctx, &content).await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[usage = "<mirror_id>"]
+#[description = "Stop mirroring new channels. The pairs already created stay, remove them with `unlink` and `channel_unlink`."]
+async fn unmirror(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mirror_id = match args.rest().split_whitespace().next().map(|a| a.parse::<i64>()) {
+        Some(Ok(mirror_id)) => mirror_id,
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments mirror_id").await?;
+            return Ok(());
+        }
+    };
+
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+    let guild_id = msg.guild_id.unwrap_or_default().get() as i64;
+
+    let mirror = match _db_access.get_replication_mirror_by_id(mirror_id) {
+        Ok(mirror) if mirror.from_guild == guild_id || mirror.to_guild == guild_id => mirror,
+        _ => {
+            msg.reply(ctx, &format!("No mirror {} in this server", mirror_id)).await?;
+            return Ok(());
+        }
+    };
+
+    match _db_access.delete_replication_mirror(mirror.id) {
+        Ok(_) => {
+            msg.reply(ctx, &format!("Mirror removed: {}", describe_mirror(&ctx.cache, &mirror))).await?;
+            write_info_log(format!("Mirror removed {:?}", mirror));
+        }
+        Err(e) => {
+            msg.reply(ctx, &format!("Error removing mirror: {}", e.message)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[usage = "<pair_id>"]
+#[description = "List the content filters of a replication pair of this server"]
+async fn filters(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let pair_id = match args.rest().split_whitespace().next().map(|a| a.parse::<i64>()) {
+        Some(Ok(pair_id)) => pair_id,
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments pair_id").await?;
+            return Ok(());
+        }
+    };
+
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+    let guild_id = msg.guild_id.unwrap_or_default().get() as i64;
+
+    match _db_access.get_replication_forum_pair_by_id(pair_id) {
+        Ok(pair) if pair.from_guild == guild_id || pair.to_guild == guild_id => {}
+        _ => {
+            msg.reply(ctx, &format!("No replication pair {} in this server", pair_id)).await?;
+            return Ok(());
+        }
+    }
+
+    let content = match _db_access.get_replication_filters(pair_id) {
+        Ok(filters) if filters.is_empty() => format!("Replication pair {} has no filter", pair_id),
+        Ok(filters) => filters.iter().map(|f| format!("#{}: {} `{}`", f.id, f.kind, f.value)).collect::<Vec<String>>().join("\n"),
+        Err(e) => format!("Error listing filters: {}", e.message),
+    };
+
+    msg.channel_id.send_message(&ctx.http, CreateMessage::new().content(content).allowed_mentions(no_mentions())).await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[usage = "<pair_id> <kind> <value>"]
+#[description = "Add a content filter to a forum replication pair of this server. Filtered messages get a 🚫 reaction instead of being replicated, \
+copies of messages edited into filtered ones are deleted. Text channel pairs are not filtered.\n\
+Kinds: `regex` (pattern), `word` (word or phrase), `prefix` (text the message starts with), `deny_author`, `allow_author` (user id), `deny_role`, `allow_role` (role id). With allow rules, only matching authors are replicated."]
+async fn filter_add(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    // The value is the rest of the line, patterns and prefixes may contain spaces
+    let mut parts = args.rest().trim().splitn(3, char::is_whitespace);
+    let (pair_id, kind, value) = match (parts.next().map(|a| a.parse::<i64>()), parts.next().and_then(FilterKind::parse), parts.next().map(str::trim)) {
+        (Some(Ok(pair_id)), Some(kind), Some(value)) => (pair_id, kind, value.to_string()),
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments pair_id kind value, kind is one of: regex, word, prefix, allow_author, deny_author, allow_role, deny_role").await?;
+            return Ok(());
+        }
+    };
+
+    if let Err(reason) = validate_filter(kind, &value) {
+        msg.reply(ctx, &format!("Cannot add filter: {}", reason)).await?;
+        return Ok(());
+    }
+
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+    let guild_id = msg.guild_id.unwrap_or_default().get() as i64;
+
+    match _db_access.get_replication_forum_pair_by_id(pair_id) {
+        Ok(pair) if pair.from_guild == guild_id || pair.to_guild == guild_id => {}
+        _ => {
+            msg.reply(ctx, &format!("No replication pair {} in this server", pair_id)).await?;
+            return Ok(());
+        }
+    }
+
+    match _db_access.create_replication_filter(ReplicationFilterData { pair_id, kind: kind.as_str().to_string(), value }) {
+        Ok(created) => {
+            msg.reply(ctx, &format!("Filter #{} added to replication pair {}", created.id, pair_id)).await?;
+            write_info_log(format!("Replication filter created {:?}", created));
+        }
+        Err(e) => {
+            msg.reply(ctx, &format!("Error adding filter: {}", e.message)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[usage = "<filter_id>"]
+#[description = "Remove a content filter from a replication pair of this server"]
+async fn filter_remove(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let filter_id = match args.rest().split_whitespace().next().map(|a| a.parse::<i64>()) {
+        Some(Ok(filter_id)) => filter_id,
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments filter_id").await?;
+            return Ok(());
+        }
+    };
+
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+    let guild_id = msg.guild_id.unwrap_or_default().get() as i64;
+
+    let filter = match _db_access.get_replication_filter_by_id(filter_id) {
+        Ok(filter) => filter,
+        Err(_) => {
+            msg.reply(ctx, &format!("No filter {} in this server", filter_id)).await?;
+            return Ok(());
+        }
+    };
+    match _db_access.get_replication_forum_pair_by_id(filter.pair_id) {
+        Ok(pair) if pair.from_guild == guild_id || pair.to_guild == guild_id => {}
+        _ => {
+            msg.reply(ctx, &format!("No filter {} in this server", filter_id)).await?;
+            return Ok(());
+        }
+    }
 
-        let data = ctx.data.read().await;
+    match _db_access.delete_replication_filter(filter.id) {
+        Ok(_) => {
+            msg.reply(ctx, &format!("Filter #{} removed from replication pair {}", filter.id, filter.pair_id)).await?;
+            write_info_log(format!("Replication filter removed {:?}", filter));
+        }
+        Err(e) => {
+            msg.reply(ctx, &format!("Error removing filter: {}", e.message)).await?;
+        }
+    }
 
-        let db_access_pool = match data.get::<DbHandler>() {
-            Some(v) => {
-                msg.reply(ctx, "Db access manager OK").await?;
-                v
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[description = "List the broken replication pairs of this server and why they broke"]
+async fn broken(ctx: &Context, msg: &Message) -> CommandResult {
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+
+    let content = match _db_access.get_broken_replication_forum_pairs_by_guild(msg.guild_id.unwrap_or_default().get() as i64) {
+        Ok(pairs) if pairs.is_empty() => "No broken replication pair in this server".to_string(),
+        Ok(pairs) => pairs.iter()
+            .map(|p| format!("{}\n  {}", describe_pair(&ctx.cache, p), p.broken_reason.clone().unwrap_or_default()))
+            .collect::<Vec<String>>()
+            .join("\n"),
+        Err(e) => format!("Error listing broken replication pairs: {}", e.message),
+    };
+
+    msg.channel_id.send_message(&ctx.http, CreateMessage::new().content(content).allowed_mentions(no_mentions())).await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[usage = "<pair_id> [source|target <forum_id>]"]
+#[description = "Check a broken replication pair again and resume it. Give `source` or `target` with a forum of that side's server to replace a deleted forum."]
+async fn repair(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let args: Vec<&str> = args.rest().split_whitespace().collect();
+    let (pair_id, replacement) = match (args.first().map(|a| a.parse::<i64>()), args.get(1), args.get(2).map(|a| a.parse::<i64>())) {
+        (Some(Ok(pair_id)), None, _) => (pair_id, None),
+        (Some(Ok(pair_id)), Some(side), Some(Ok(forum_id))) if *side == "source" || *side == "target" => (pair_id, Some((*side, forum_id))),
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments pair_id [source|target forum_id]").await?;
+            return Ok(());
+        }
+    };
+
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+    let guild_id = msg.guild_id.unwrap_or_default().get() as i64;
+
+    let pair = match _db_access.get_replication_forum_pair_by_id(pair_id) {
+        Ok(pair) if pair.from_guild == guild_id || pair.to_guild == guild_id => pair,
+        _ => {
+            msg.reply(ctx, &format!("No replication pair {} in this server", pair_id)).await?;
+            return Ok(());
+        }
+    };
+    if pair.broken_reason.is_none() {
+        msg.reply(ctx, &format!("Replication pair {} is not broken", pair.id)).await?;
+        return Ok(());
+    }
+
+    // The replacement forum stays in the server of the side it replaces
+    let to_update = ReplicationForumPairData {
+        from_guild: pair.from_guild,
+        from_forum: match replacement {
+            Some(("source", forum_id)) => forum_id,
+            _ => pair.from_forum,
+        },
+        to_guild: pair.to_guild,
+        to_forum: match replacement {
+            Some(("target", forum_id)) => forum_id,
+            _ => pair.to_forum,
+        },
+    };
+
+    if let Err(e) = validate_forum_pair(ctx, msg.author.id, to_update.from_guild, to_update.from_forum, to_update.to_guild, to_update.to_forum).await {
+        msg.reply(ctx, &format!("Replication pair {} is still broken: {}", pair.id, e.message)).await?;
+        return Ok(());
+    }
+
+    let repaired = _db_access.update_replication_forum_pair(pair.id, to_update)
+        .and_then(|updated| _db_access.update_replication_forum_pair_broken_reason(updated.id, None));
+    match repaired {
+        Ok(repaired) => {
+            msg.reply(ctx, &format!("Replication pair repaired: {}", describe_pair(&ctx.cache, &repaired))).await?;
+            write_info_log(format!("Replication pair repaired {:?}", repaired));
+        }
+        Err(e) => {
+            msg.reply(ctx, &format!("Error repairing replication pair: {}", e.message)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[usage = "<pair_id> <setting> <value>"]
+#[description = "Change a setting of a replication pair created from this server.\n\
+Settings: `on_delete` (delete, mark, keep), `attachments` (link, notice), `webhook` (on, off), `reactions` (on, off), `on_expiry` (decline, accept), `approval` (ask_owner, auto_accept, auto_decline, moderator_only, target_moderators), `thread_sync` (mirror, annotate, off), `origin_label` (off, tag, prefix)"]
+async fn pair_set(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let args: Vec<&str> = args.rest().split_whitespace().collect();
+    let (pair_id, setting, value) = match (args.first().map(|a| a.parse::<i64>()), args.get(1), args.get(2)) {
+        (Some(Ok(pair_id)), Some(setting), Some(value)) => (pair_id, setting.to_string(), value.to_string()),
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments pair_id setting value").await?;
+            return Ok(());
+        }
+    };
+
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+
+    match _db_access.get_replication_forum_pair_by_id(pair_id) {
+        Ok(pair) if pair.from_guild == msg.guild_id.unwrap_or_default().get() as i64 => {}
+        _ => {
+            msg.reply(ctx, &format!("No replication pair {} from this server", pair_id)).await?;
+            return Ok(());
+        }
+    }
+
+    let updated = match setting.as_str() {
+        "on_delete" => match DeletePolicy::parse(&value) {
+            Some(policy) => _db_access.update_replication_forum_pair_delete_policy(pair_id, policy),
+            None => {
+                msg.reply(ctx, "`on_delete` must be one of: delete, mark, keep").await?;
+                return Ok(());
             }
+        },
+        "attachments" => match AttachmentFallback::parse(&value) {
+            Some(fallback) => _db_access.update_replication_forum_pair_attachment_fallback(pair_id, fallback),
             None => {
-                msg.reply(ctx, "There was a problem getting the db access manager").await?;
-
+                msg.reply(ctx, "`attachments` must be one of: link, notice").await?;
                 return Ok(());
             }
-        };
-
-        let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
-
-        let to_insert = ReplicationForumPairData {
-            from_guild: from_guild.clone().unwrap(),
-            from_forum: from_forum.clone().unwrap(),
-            to_guild: to_guild.clone().unwrap(),
-            to_forum: to_forum.clone().unwrap(),
-        };
-        match _db_access.create_replication_forum_pair(to_insert) {
-            Ok(created) => {
-                msg.reply(ctx, "Replication pair created").await?;
-                write_info_log(format!("Replication pair created {:?}", created));
+        },
+        "webhook" => match value.as_str() {
+            "on" => _db_access.update_replication_forum_pair_use_webhook(pair_id, true),
+            "off" => _db_access.update_replication_forum_pair_use_webhook(pair_id, false),
+            _ => {
+                msg.reply(ctx, "`webhook` must be one of: on, off").await?;
+                return Ok(());
             }
-            Err(e) => {
-                msg.reply(ctx, &format!("Error creating replication pair: {:?}", e)).await?;
+        },
+        "reactions" => match value.as_str() {
+            "on" => _db_access.update_replication_forum_pair_mirror_reactions(pair_id, true),
+            "off" => _db_access.update_replication_forum_pair_mirror_reactions(pair_id, false),
+            _ => {
+                msg.reply(ctx, "`reactions` must be one of: on, off").await?;
+                return Ok(());
+            }
+        },
+        "on_expiry" => match ExpiryPolicy::parse(&value) {
+            Some(policy) => _db_access.update_replication_forum_pair_expiry_policy(pair_id, policy),
+            None => {
+                msg.reply(ctx, "`on_expiry` must be one of: decline, accept").await?;
+                return Ok(());
+            }
+        },
+        "approval" => match ApprovalPolicy::parse(&value) {
+            Some(policy) => _db_access.update_replication_forum_pair_approval_policy(pair_id, policy),
+            None => {
+                msg.reply(ctx, "`approval` must be one of: ask_owner, auto_accept, auto_decline, moderator_only, target_moderators").await?;
+                return Ok(());
+            }
+        },
+        "thread_sync" => match ThreadSync::parse(&value) {
+            Some(sync) => _db_access.update_replication_forum_pair_thread_sync(pair_id, sync),
+            None => {
+                msg.reply(ctx, "`thread_sync` must be one of: mirror, annotate, off").await?;
+                return Ok(());
+            }
+        },
+        "origin_label" => match OriginLabel::parse(&value) {
+            Some(label) => _db_access.update_replication_forum_pair_origin_label(pair_id, label),
+            None => {
+                msg.reply(ctx, "`origin_label` must be one of: off, tag, prefix").await?;
+                return Ok(());
             }
+        },
+        _ => {
+            msg.reply(ctx, &format!("Unknown setting `{}`", setting)).await?;
+            return Ok(());
         }
+    };
 
-        drop(data);
-
-        msg.channel_id.say(&ctx.http, &format!("from_guild: {}, from_channel: {}, to_guild: {}, to_channel: {}",
-                                               from_guild.unwrap(),
-                                               from_forum.unwrap(),
-                                               to_guild.unwrap(),
-                                               to_forum.unwrap()
-        )).await?;
-    } else {
-        msg.channel_id.say(&ctx.http, "Invalid arguments from_guild_id from_channel_id to_guild_id to_channel_id").await?;
+    match updated {
+        Ok(pair) => {
+            msg.reply(ctx, &format!("Replication pair {}: `{}` set to `{}`", pair.id, setting, value)).await?;
+            write_info_log(format!("Replication pair updated {:?}", pair));
+        }
+        Err(e) => {
+            msg.reply(ctx, &format!("Error updating replication pair: {}", e.message)).await?;
+        }
     }
 
     Ok(())
@@ -301,3 +1145,289 @@ pub(crate) async fn create_framework(owners: HashSet<UserId>, bot_id: UserId) ->
 
     framework
 }
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("ADMINISTRATOR")]
+#[usage = "[channel_id]"]
+#[description = "Post the replication requests of `target_moderators` pairs targeting this server in a channel, the current one by default."]
+async fn mod_channel(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let guild_id = msg.guild_id.unwrap_or_default();
+    let channel_id = match args.rest().split_whitespace().next().map(|a| a.parse::<u64>()) {
+        None => msg.channel_id,
+        Some(Ok(channel_id)) if channel_id != 0 => ChannelId::new(channel_id),
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments [channel_id]").await?;
+            return Ok(());
+        }
+    };
+
+    let is_text_channel = ctx.cache.guild(guild_id)
+        .and_then(|g| g.channels.get(&channel_id).map(|c| c.kind == ChannelType::Text))
+        .unwrap_or(false);
+    if !is_text_channel {
+        msg.reply(ctx, &format!("Channel `{}` is not a text channel of this server", channel_id)).await?;
+        return Ok(());
+    }
+
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+
+    match _db_access.set_replication_moderation_channel(ReplicationModerationChannelData {
+        guild_id: guild_id.get() as i64,
+        channel_id: channel_id.get() as i64,
+    }) {
+        Ok(_) => {
+            msg.reply(ctx, &format!("Replication requests will be posted in <#{}>", channel_id)).await?;
+            write_info_log(format!("Moderation channel of guild {} set to {}", guild_id, channel_id));
+        }
+        Err(e) => {
+            msg.reply(ctx, &format!("Error setting the moderation channel: {}", e.message)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("MANAGE_THREADS")]
+#[description = "List the replication requests waiting for the moderators of this server"]
+async fn queue(ctx: &Context, msg: &Message) -> CommandResult {
+    let data = ctx.data.read().await;
+
+    let db_access_pool = match data.get::<DbHandler>() {
+        Some(v) => v,
+        None => {
+            msg.reply(ctx, "There was a problem getting the db access manager").await?;
+
+            return Ok(());
+        }
+    };
+
+    let _db_access: DBAccessManager = db_access_pool.mut_as_db_access();
+
+    let content = match _db_access.get_replication_queue_by_guild(msg.guild_id.unwrap_or_default().get() as i64) {
+        Ok(entries) if entries.is_empty() => "No replication request waiting".to_string(),
+        Ok(entries) => entries.iter()
+            .map(|(entry, (reply, pair))| queue_description(&ctx.cache, entry.id, reply, pair))
+            .collect::<Vec<String>>()
+            .join("\n"),
+        Err(e) => format!("Error listing replication requests: {}", e.message),
+    };
+
+    msg.channel_id.send_message(&ctx.http, CreateMessage::new().content(content).allowed_mentions(no_mentions())).await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("MANAGE_THREADS")]
+#[usage = "<all|queue_id...>"]
+#[description = "Approve queued replication requests of this server"]
+async fn queue_approve(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    process_queue(ctx, msg, args, true).await
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("MANAGE_THREADS")]
+#[usage = "<all|queue_id...>"]
+#[description = "Reject queued replication requests of this server"]
+async fn queue_reject(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    process_queue(ctx, msg, args, false).await
+}
+
+async fn process_queue(ctx: &Context, msg: &Message, args: Args, accept: bool) -> CommandResult {
+    let args: Vec<&str> = args.rest().split_whitespace().collect();
+    let guild_id = msg.guild_id.unwrap_or_default().get() as i64;
+
+    let (handler, waiting) = {
+        let data = ctx.data.read().await;
+
+        let handler = match data.get::<Handler>() {
+            Some(v) => v.clone(),
+            None => {
+                msg.reply(ctx, "There was a problem getting the replication handler").await?;
+
+                return Ok(());
+            }
+        };
+
+        let waiting: Vec<i64> = match handler.get_access().and_then(|db| db.get_replication_queue_by_guild(guild_id)) {
+            Ok(entries) => entries.iter().map(|(entry, _)| entry.id).collect(),
+            Err(e) => {
+                msg.reply(ctx, &format!("Error listing replication requests: {}", e.message)).await?;
+                return Ok(());
+            }
+        };
+
+        (handler, waiting)
+    };
+
+    let queue_ids: Vec<i64> = match args.as_slice() {
+        ["all"] => waiting.clone(),
+        [] => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments all|queue_id...").await?;
+            return Ok(());
+        }
+        ids => match ids.iter().map(|a| a.parse::<i64>()).collect::<Result<Vec<i64>, _>>() {
+            Ok(ids) => ids,
+            Err(_) => {
+                msg.channel_id.say(&ctx.http, "Invalid arguments all|queue_id...").await?;
+                return Ok(());
+            }
+        },
+    };
+
+    let mut report = Vec::new();
+    for queue_id in queue_ids {
+        if !waiting.contains(&queue_id) {
+            report.push(format!("#{}: not waiting in this server", queue_id));
+            continue;
+        }
+
+        match handler.process_queue_entry(ctx, queue_id, accept, Some(msg.author.id.get() as i64)).await {
+            Ok(outcome) => report.push(format!("#{}: {}", queue_id, outcome)),
+            Err(e) => report.push(format!("#{}: {}", queue_id, e.message)),
+        }
+    }
+
+    let content = if report.is_empty() { "No replication request waiting".to_string() } else { report.join("\n") };
+    msg.channel_id.send_message(&ctx.http, CreateMessage::new().content(content).allowed_mentions(no_mentions())).await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("MANAGE_THREADS")]
+#[usage = "[thread_id]"]
+#[description = "Replay the history of a replicated thread, the current one by default, into its paired threads. Messages already replicated are skipped."]
+async fn backfill(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let thread_id = match args.rest().split_whitespace().next().map(|a| a.parse::<u64>()) {
+        None => msg.channel_id,
+        Some(Ok(thread_id)) if thread_id != 0 => ChannelId::new(thread_id),
+        _ => {
+            msg.channel_id.say(&ctx.http, "Invalid arguments [thread_id]").await?;
+            return Ok(());
+        }
+    };
+    let guild_id = msg.guild_id.unwrap_or_default().get() as i64;
+
+    let handler = {
+        let data = ctx.data.read().await;
+
+        match data.get::<Handler>() {
+            Some(v) => v.clone(),
+            None => {
+                msg.reply(ctx, "There was a problem getting the replication handler").await?;
+
+                return Ok(());
+            }
+        }
+    };
+
+    let thread_pairs = match handler.get_access().and_then(|db| db.get_replication_thread_pairs(guild_id, thread_id.get() as i64)) {
+        Ok(thread_pairs) if !thread_pairs.is_empty() => thread_pairs,
+        Ok(_) => {
+            msg.reply(ctx, &format!("<#{}> is not replicated anywhere", thread_id)).await?;
+            return Ok(());
+        }
+        Err(e) => {
+            msg.reply(ctx, &format!("Error getting replicated threads: {}", e.message)).await?;
+            return Ok(());
+        }
+    };
+
+    let mut report = Vec::new();
+    for f in thread_pairs {
+        let started = handler.start_backfill(ctx, ReplicationBackfillJobData {
+            from_guild: f.from_guild,
+            from_thread: f.from_thread,
+            to_guild: f.to_guild,
+            to_thread: f.to_thread,
+        });
+
+        report.push(match started {
+            Ok((job, true)) => format!("Backfill #{} into <#{}> started", job.id, f.to_thread),
+            Ok((job, false)) => format!("Backfill #{} into <#{}> is already running", job.id, f.to_thread),
+            Err(e) => format!("Error starting backfill into <#{}>: {}", f.to_thread, e.message),
+        });
+    }
+
+    msg.reply(ctx, &report.join("\n")).await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("MANAGE_THREADS")]
+#[usage = "[reason]"]
+#[description = "Pause the replication of the current thread in both directions, until `resume`"]
+async fn pause(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let reason = Some(args.rest().trim()).filter(|reason| !reason.is_empty()).map(str::to_string);
+    set_thread_status(ctx, msg, ReplicationStatus::Paused, reason).await
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("MANAGE_THREADS")]
+#[description = "Resume the replication of the current thread after `pause`"]
+async fn resume(ctx: &Context, msg: &Message) -> CommandResult {
+    set_thread_status(ctx, msg, ReplicationStatus::Accepted, None).await
+}
+
+// Moves the replication requests of the current thread to `status`, from either side of the pair.
+async fn set_thread_status(ctx: &Context, msg: &Message, status: ReplicationStatus, reason: Option<String>) -> CommandResult {
+    let guild_id = msg.guild_id.unwrap_or_default().get() as i64;
+
+    let handler = {
+        let data = ctx.data.read().await;
+
+        match data.get::<Handler>() {
+            Some(v) => v.clone(),
+            None => {
+                msg.reply(ctx, "There was a problem getting the replication handler").await?;
+
+                return Ok(());
+            }
+        }
+    };
+
+    let actor_id = Some(msg.author.id.get() as i64);
+    let outcomes = match handler.get_access().and_then(|db| db.transition_thread_replication(guild_id, msg.channel_id.get() as i64, status, actor_id, reason)) {
+        Ok(outcomes) if !outcomes.is_empty() => outcomes,
+        Ok(_) => {
+            msg.reply(ctx, &format!("<#{}> is not replicated anywhere", msg.channel_id)).await?;
+            return Ok(());
+        }
+        Err(e) => {
+            msg.reply(ctx, &format!("Error getting replicated threads: {}", e.message)).await?;
+            return Ok(());
+        }
+    };
+
+    let report: Vec<String> = outcomes.into_iter()
+        .map(|(to_thread, outcome)| match outcome {
+            Ok(_) => format!("Replication with <#{}> is now {}", to_thread, status.as_str()),
+            Err(e) => format!("Replication with <#{}>: {}", to_thread, e.message),
+        })
+        .collect();
+
+    msg.reply(ctx, &report.join("\n")).await?;
+
+    Ok(())
+}
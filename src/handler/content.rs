@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use regex::{Captures, Regex};
+use serenity::all::{Cache, ChannelId, CreateAllowedMentions, GuildId, RoleId, UserId};
+use crate::database::DBAccessManager;
+
+fn mention_regex() -> &'static Regex {
+    static MENTION: OnceLock<Regex> = OnceLock::new();
+    MENTION.get_or_init(|| Regex::new(r"<(@!?|@&|#)(\d+)>").unwrap())
+}
+
+// Replicated messages never ping: mentions are rewritten to plain names and mass mentions suppressed.
+pub fn no_mentions() -> CreateAllowedMentions {
+    CreateAllowedMentions::new().everyone(false).all_users(false).all_roles(false)
+}
+
+/// Channels of the source guild mentioned in `content` that are paired with a thread or channel of `target_guild`.
+pub fn paired_channels(db: &DBAccessManager, source_guild: i64, target_guild: i64, content: &str) -> HashMap<u64, u64> {
+    let mut paired = HashMap::new();
+
+    for captures in mention_regex().captures_iter(content) {
+        if &captures[1] != "#" {
+            continue;
+        }
+        let channel_id = match captures[2].parse::<u64>() {
+            Ok(channel_id) => channel_id,
+            Err(_) => continue,
+        };
+
+        if let Ok(pairs) = db.get_replication_thread_pairs(source_guild, channel_id as i64) {
+            if let Some(pair) = pairs.iter().find(|p| p.to_guild == target_guild) {
+                paired.insert(channel_id, pair.to_thread as u64);
+                continue;
+            }
+        }
+        if let Ok(pairs) = db.get_replication_channel_pairs(source_guild, channel_id as i64) {
+            if let Some(pair) = pairs.iter().find(|p| p.to_guild == target_guild) {
+                paired.insert(channel_id, pair.to_channel as u64);
+            }
+        }
+    }
+
+    paired
+}
+
+/// Rewrites user, role and channel mentions of `source_guild` so they read the same in another guild.
+/// Channels found in `paired` point to their counterpart, anything else becomes a plain name.
+pub fn sanitize_mentions(cache: &Cache, source_guild: GuildId, content: &str, paired: &HashMap<u64, u64>) -> String {
+    let guild = cache.guild(source_guild);
+
+    mention_regex().replace_all(content, |captures: &Captures| {
+        let id = match captures[2].parse::<u64>() {
+            Ok(id) if id != 0 => id,
+            _ => return captures[0].to_string(),
+        };
+
+        match &captures[1] {
+            "#" => {
+                if let Some(paired_id) = paired.get(&id) {
+                    return format!("<#{}>", paired_id);
+                }
+                let name = guild.as_ref().and_then(|g| {
+                    g.channels.get(&ChannelId::new(id)).map(|c| c.name.clone())
+                        .or_else(|| g.threads.iter().find(|t| t.id == id).map(|t| t.name.clone()))
+                });
+                format!("#{}", name.unwrap_or_else(|| "unknown-channel".to_string()))
+            }
+            "@&" => {
+                let name = guild.as_ref().and_then(|g| g.roles.get(&RoleId::new(id)).map(|r| r.name.clone()));
+                format!("@{}", name.unwrap_or_else(|| "unknown-role".to_string()))
+            }
+            _ => {
+                let name = guild.as_ref()
+                    .and_then(|g| g.members.get(&UserId::new(id)).map(|m| m.display_name().to_string()))
+                    .or_else(|| cache.user(id).map(|u| u.name.clone()));
+                format!("@{}", name.unwrap_or_else(|| "unknown-user".to_string()))
+            }
+        }
+    }).into_owned()
+}
@@ -6,6 +6,17 @@ use crate::{
         replications_forum_pairs,
         replications_reply,
         replication_thread_pairs,
+        replication_channel_pairs,
+        replication_filters,
+        replication_message_pairs,
+        replication_webhooks,
+        replication_reaction_counts,
+        replication_status_events,
+        replication_mirrors,
+        replication_mirror_channels,
+        replication_moderation_channels,
+        replication_queue,
+        replication_backfill_jobs,
     },
     errors::ErrorType,
     log::{write_debug_log, write_error_log},
@@ -23,9 +34,248 @@ pub struct ReplicationForumPair {
     pub to_guild: i64,
     pub to_forum: i64,
     pub created_at: NaiveDateTime,
+    pub on_message_delete: String,
+    pub attachment_fallback: String,
+    pub use_webhook: bool,
+    pub mirror_reactions: bool,
+    pub on_prompt_expiry: String,
+    pub approval_policy: String,
+    pub thread_sync: String,
+    pub broken_reason: Option<String>,
+    pub origin_label: String,
 }
 
-#[derive(Insertable, Serialize, Deserialize)]
+impl ReplicationForumPair {
+    pub fn delete_policy(&self) -> DeletePolicy {
+        DeletePolicy::parse(&self.on_message_delete).unwrap_or(DeletePolicy::Delete)
+    }
+
+    pub fn attachment_fallback(&self) -> AttachmentFallback {
+        AttachmentFallback::parse(&self.attachment_fallback).unwrap_or(AttachmentFallback::Link)
+    }
+
+    pub fn expiry_policy(&self) -> ExpiryPolicy {
+        ExpiryPolicy::parse(&self.on_prompt_expiry).unwrap_or(ExpiryPolicy::Decline)
+    }
+
+    pub fn approval_policy(&self) -> ApprovalPolicy {
+        ApprovalPolicy::parse(&self.approval_policy).unwrap_or(ApprovalPolicy::AskOwner)
+    }
+
+    pub fn thread_sync(&self) -> ThreadSync {
+        ThreadSync::parse(&self.thread_sync).unwrap_or(ThreadSync::Annotate)
+    }
+
+    pub fn origin_label(&self) -> OriginLabel {
+        OriginLabel::parse(&self.origin_label).unwrap_or(OriginLabel::Off)
+    }
+}
+
+/// What happens to replicated copies when their source message is deleted.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeletePolicy {
+    Delete,
+    Mark,
+    Keep,
+}
+
+impl DeletePolicy {
+    pub fn parse(value: &str) -> Option<DeletePolicy> {
+        match value {
+            "delete" => Some(DeletePolicy::Delete),
+            "mark" => Some(DeletePolicy::Mark),
+            "keep" => Some(DeletePolicy::Keep),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeletePolicy::Delete => "delete",
+            DeletePolicy::Mark => "mark",
+            DeletePolicy::Keep => "keep",
+        }
+    }
+}
+
+/// How attachments over the target guild's upload limit are shown in the replicated message.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachmentFallback {
+    Link,
+    Notice,
+}
+
+impl AttachmentFallback {
+    pub fn parse(value: &str) -> Option<AttachmentFallback> {
+        match value {
+            "link" => Some(AttachmentFallback::Link),
+            "notice" => Some(AttachmentFallback::Notice),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentFallback::Link => "link",
+            AttachmentFallback::Notice => "notice",
+        }
+    }
+}
+
+/// What a pairing prompt left unanswered until it expires does for this pair's target.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpiryPolicy {
+    Decline,
+    Accept,
+}
+
+impl ExpiryPolicy {
+    pub fn parse(value: &str) -> Option<ExpiryPolicy> {
+        match value {
+            "decline" => Some(ExpiryPolicy::Decline),
+            "accept" => Some(ExpiryPolicy::Accept),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExpiryPolicy::Decline => "decline",
+            ExpiryPolicy::Accept => "accept",
+        }
+    }
+}
+
+/// Who decides whether a new post of the source forum is replicated to this pair's target.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalPolicy {
+    AskOwner,
+    AutoAccept,
+    AutoDecline,
+    ModeratorOnly,
+    TargetModerators,
+}
+
+impl ApprovalPolicy {
+    pub fn parse(value: &str) -> Option<ApprovalPolicy> {
+        match value {
+            "ask_owner" => Some(ApprovalPolicy::AskOwner),
+            "auto_accept" => Some(ApprovalPolicy::AutoAccept),
+            "auto_decline" => Some(ApprovalPolicy::AutoDecline),
+            "moderator_only" => Some(ApprovalPolicy::ModeratorOnly),
+            "target_moderators" => Some(ApprovalPolicy::TargetModerators),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalPolicy::AskOwner => "ask_owner",
+            ApprovalPolicy::AutoAccept => "auto_accept",
+            ApprovalPolicy::AutoDecline => "auto_decline",
+            ApprovalPolicy::ModeratorOnly => "moderator_only",
+            ApprovalPolicy::TargetModerators => "target_moderators",
+        }
+    }
+}
+
+/// How a rename, archive, lock or deletion of a replicated thread reaches its counterpart.
+/// Only the source thread is ever mirrored onto its copies, changes made on a copy at most annotate
+/// the source.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadSync {
+    Mirror,
+    Annotate,
+    Off,
+}
+
+impl ThreadSync {
+    pub fn parse(value: &str) -> Option<ThreadSync> {
+        match value {
+            "mirror" => Some(ThreadSync::Mirror),
+            "annotate" => Some(ThreadSync::Annotate),
+            "off" => Some(ThreadSync::Off),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThreadSync::Mirror => "mirror",
+            ThreadSync::Annotate => "annotate",
+            ThreadSync::Off => "off",
+        }
+    }
+}
+
+/// How a replicated post shows the server it comes from, for forums fed by several sources.
+/// `tag` applies the target forum tag named after the source server, or falls back to `prefix`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OriginLabel {
+    Off,
+    Tag,
+    Prefix,
+}
+
+impl OriginLabel {
+    pub fn parse(value: &str) -> Option<OriginLabel> {
+        match value {
+            "off" => Some(OriginLabel::Off),
+            "tag" => Some(OriginLabel::Tag),
+            "prefix" => Some(OriginLabel::Prefix),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OriginLabel::Off => "off",
+            OriginLabel::Tag => "tag",
+            OriginLabel::Prefix => "prefix",
+        }
+    }
+}
+
+/// Rule of a pair's content filter, see `filters::filter_reason` for how they combine.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterKind {
+    Regex,
+    Word,
+    Prefix,
+    AllowAuthor,
+    DenyAuthor,
+    AllowRole,
+    DenyRole,
+}
+
+impl FilterKind {
+    pub fn parse(value: &str) -> Option<FilterKind> {
+        match value {
+            "regex" => Some(FilterKind::Regex),
+            "word" => Some(FilterKind::Word),
+            "prefix" => Some(FilterKind::Prefix),
+            "allow_author" => Some(FilterKind::AllowAuthor),
+            "deny_author" => Some(FilterKind::DenyAuthor),
+            "allow_role" => Some(FilterKind::AllowRole),
+            "deny_role" => Some(FilterKind::DenyRole),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilterKind::Regex => "regex",
+            FilterKind::Word => "word",
+            FilterKind::Prefix => "prefix",
+            FilterKind::AllowAuthor => "allow_author",
+            FilterKind::DenyAuthor => "deny_author",
+            FilterKind::AllowRole => "allow_role",
+            FilterKind::DenyRole => "deny_role",
+        }
+    }
+}
+
+#[derive(Insertable, AsChangeset, Serialize, Deserialize)]
 #[table_name = "replications_forum_pairs"]
 pub struct ReplicationForumPairData {
     pub from_guild: i64,
@@ -37,7 +287,6 @@ pub struct ReplicationForumPairData {
 #[derive(Queryable, Serialize, Deserialize, Debug)]
 pub struct ReplicationReply {
     pub id: i64,
-    pub responded: bool,
     pub status: String,
     pub guild_id: i64,
     pub created_at: NaiveDateTime,
@@ -45,12 +294,68 @@ pub struct ReplicationReply {
     pub replication_pairs: i64,
     pub message_id: Option<i64>,
     pub message_owner: i64,
+    pub reminded_at: Option<NaiveDateTime>,
+}
+
+impl ReplicationReply {
+    pub fn current_status(&self) -> ReplicationStatus {
+        ReplicationStatus::parse(&self.status).unwrap_or(ReplicationStatus::Pending)
+    }
+}
+
+/// Lifecycle of a replication request for one target forum.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplicationStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Expired,
+    Paused,
+    Revoked,
+}
+
+impl ReplicationStatus {
+    pub fn parse(value: &str) -> Option<ReplicationStatus> {
+        match value {
+            "pending" => Some(ReplicationStatus::Pending),
+            "accepted" => Some(ReplicationStatus::Accepted),
+            "declined" => Some(ReplicationStatus::Declined),
+            "expired" => Some(ReplicationStatus::Expired),
+            "paused" => Some(ReplicationStatus::Paused),
+            "revoked" => Some(ReplicationStatus::Revoked),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReplicationStatus::Pending => "pending",
+            ReplicationStatus::Accepted => "accepted",
+            ReplicationStatus::Declined => "declined",
+            ReplicationStatus::Expired => "expired",
+            ReplicationStatus::Paused => "paused",
+            ReplicationStatus::Revoked => "revoked",
+        }
+    }
+
+    /// The only place deciding which status may follow which: a pending request is answered once,
+    /// an accepted one can be paused and resumed, or declined when its replication fails, and
+    /// declined, expired or revoked ones are final.
+    pub fn can_transition_to(&self, next: ReplicationStatus) -> bool {
+        use ReplicationStatus::*;
+
+        matches!(
+            (self, next),
+            (Pending, Accepted) | (Pending, Declined) | (Pending, Expired)
+                | (Accepted, Paused) | (Accepted, Revoked) | (Accepted, Declined)
+                | (Paused, Accepted) | (Paused, Revoked)
+        )
+    }
 }
 
 #[derive(Insertable, Serialize, Deserialize, Debug)]
 #[table_name = "replications_reply"]
 pub struct ReplicationReplyData {
-    pub responded: bool,
     pub status: String,
     pub guild_id: i64,
     pub channel_id: i64,
@@ -59,6 +364,30 @@ pub struct ReplicationReplyData {
     pub message_owner: i64,
 }
 
+#[derive(Queryable, Serialize, Deserialize, Debug)]
+pub struct ReplicationStatusEvent {
+    pub id: i64,
+    pub replication_reply_id: i64,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub actor_id: Option<i64>,
+    pub reason: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, Debug)]
+#[table_name = "replication_status_events"]
+pub struct ReplicationStatusEventData {
+    pub replication_reply_id: i64,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub actor_id: Option<i64>,
+    pub reason: Option<String>,
+}
+
+/// Target thread of a pair and the outcome of moving its replication request to a new status.
+pub type ThreadTransition = (i64, Result<ReplicationReply, AppError>);
+
 #[derive(Queryable, Serialize, Deserialize, Debug)]
 pub struct ReplicationThreadPair {
     pub id: i64,
@@ -80,12 +409,222 @@ pub struct ReplicationThreadPairData {
     pub replication_reply_id: i64,
 }
 
+#[derive(Queryable, Serialize, Deserialize, Debug)]
+pub struct ReplicationChannelPair {
+    pub id: i64,
+    pub from_guild: i64,
+    pub from_channel: i64,
+    pub to_guild: i64,
+    pub to_channel: i64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, Debug)]
+#[table_name = "replication_channel_pairs"]
+pub struct ReplicationChannelPairData {
+    pub from_guild: i64,
+    pub from_channel: i64,
+    pub to_guild: i64,
+    pub to_channel: i64,
+}
+
+/// A source category, or a whole source guild when `from_category` is `None`, mirrored into `to_guild`.
+#[derive(Queryable, Serialize, Deserialize, Debug, Clone)]
+pub struct ReplicationMirror {
+    pub id: i64,
+    pub from_guild: i64,
+    pub from_category: Option<i64>,
+    pub to_guild: i64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, Debug)]
+#[table_name = "replication_mirrors"]
+pub struct ReplicationMirrorData {
+    pub from_guild: i64,
+    pub from_category: Option<i64>,
+    pub to_guild: i64,
+}
+
+#[derive(Queryable, Serialize, Deserialize, Debug)]
+pub struct ReplicationMirrorChannel {
+    pub id: i64,
+    pub mirror_id: i64,
+    pub from_channel: i64,
+    pub to_channel: i64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, Debug)]
+#[table_name = "replication_mirror_channels"]
+pub struct ReplicationMirrorChannelData {
+    pub mirror_id: i64,
+    pub from_channel: i64,
+    pub to_channel: i64,
+}
+
+#[derive(Queryable, Serialize, Deserialize, Debug)]
+pub struct ReplicationFilter {
+    pub id: i64,
+    pub pair_id: i64,
+    pub kind: String,
+    pub value: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl ReplicationFilter {
+    pub fn kind(&self) -> Option<FilterKind> {
+        FilterKind::parse(&self.kind)
+    }
+}
+
+#[derive(Insertable, Serialize, Deserialize, Debug)]
+#[table_name = "replication_filters"]
+pub struct ReplicationFilterData {
+    pub pair_id: i64,
+    pub kind: String,
+    pub value: String,
+}
+
+#[derive(Queryable, Serialize, Deserialize, Debug)]
+pub struct ReplicationMessagePair {
+    pub id: i64,
+    pub from_guild: i64,
+    pub from_channel: i64,
+    pub from_message: i64,
+    pub to_guild: i64,
+    pub to_channel: i64,
+    pub to_message: i64,
+    pub created_at: NaiveDateTime,
+    pub webhook_id: Option<i64>,
+    // The message a chain of copies started from, and how many copies away from it this one is
+    pub origin_guild: i64,
+    pub origin_channel: i64,
+    pub origin_message: i64,
+    pub hops: i32,
+}
+
+#[derive(Insertable, Serialize, Deserialize, Debug)]
+#[table_name = "replication_message_pairs"]
+pub struct ReplicationMessagePairData {
+    pub from_guild: i64,
+    pub from_channel: i64,
+    pub from_message: i64,
+    pub to_guild: i64,
+    pub to_channel: i64,
+    pub to_message: i64,
+    pub webhook_id: Option<i64>,
+    pub origin_guild: i64,
+    pub origin_channel: i64,
+    pub origin_message: i64,
+    pub hops: i32,
+}
+
+#[derive(Queryable, Serialize, Deserialize, Debug)]
+pub struct ReplicationReactionCount {
+    pub id: i64,
+    pub channel_id: i64,
+    pub message_id: i64,
+    pub emoji: String,
+    pub count: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, Debug)]
+#[table_name = "replication_reaction_counts"]
+pub struct ReplicationReactionCountData {
+    pub channel_id: i64,
+    pub message_id: i64,
+    pub emoji: String,
+    pub count: i32,
+}
+
+#[derive(Queryable, Serialize, Deserialize, Debug)]
+pub struct ReplicationWebhook {
+    pub id: i64,
+    pub guild_id: i64,
+    pub channel_id: i64,
+    pub webhook_id: i64,
+    pub webhook_token: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, Debug)]
+#[table_name = "replication_webhooks"]
+pub struct ReplicationWebhookData {
+    pub guild_id: i64,
+    pub channel_id: i64,
+    pub webhook_id: i64,
+    pub webhook_token: String,
+}
+
+#[derive(Queryable, Serialize, Deserialize, Debug)]
+pub struct ReplicationModerationChannel {
+    pub id: i64,
+    pub guild_id: i64,
+    pub channel_id: i64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, Debug)]
+#[table_name = "replication_moderation_channels"]
+pub struct ReplicationModerationChannelData {
+    pub guild_id: i64,
+    pub channel_id: i64,
+}
+
+/// A queued request with the replication request and forum pair it is about.
+pub type QueueRow = (ReplicationQueueEntry, (ReplicationReply, ReplicationForumPair));
+
+#[derive(Queryable, Serialize, Deserialize, Debug, Clone)]
+pub struct ReplicationQueueEntry {
+    pub id: i64,
+    pub replication_reply_id: i64,
+    pub guild_id: i64,
+    pub channel_id: i64,
+    pub message_id: Option<i64>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, Debug)]
+#[table_name = "replication_queue"]
+pub struct ReplicationQueueEntryData {
+    pub replication_reply_id: i64,
+    pub guild_id: i64,
+    pub channel_id: i64,
+    pub message_id: Option<i64>,
+}
+
+#[derive(Queryable, Serialize, Deserialize, Debug, Clone)]
+pub struct ReplicationBackfillJob {
+    pub id: i64,
+    pub from_guild: i64,
+    pub from_thread: i64,
+    pub to_guild: i64,
+    pub to_thread: i64,
+    pub last_message: Option<i64>,
+    pub status: String,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, Debug)]
+#[table_name = "replication_backfill_jobs"]
+pub struct ReplicationBackfillJobData {
+    pub from_guild: i64,
+    pub from_thread: i64,
+    pub to_guild: i64,
+    pub to_thread: i64,
+}
+
 impl DBAccessManager {
     pub fn get_replication_forum_pair(&self, _guild_id: i64, _channel_id: i64) -> Result<Vec<ReplicationForumPair>, AppError> {
         use crate::schema::replications_forum_pairs::dsl::*;
 
+        // Broken pairs stop offering new posts until they are repaired
         replications_forum_pairs
             .filter(from_guild.eq(_guild_id).and(from_forum.eq(_channel_id)))
+            .filter(broken_reason.is_null())
             .get_results(&self.connection)
             .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationPair"))
     }
@@ -99,68 +638,296 @@ impl DBAccessManager {
             .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationPair"))
     }
 
-    pub fn create_replication_forum_pair(&self, dto: ReplicationForumPairData) -> Result<ReplicationForumPair, AppError> {
-        diesel::insert_into(replications_forum_pairs::table)
-            .values(&dto)
+    pub fn update_replication_forum_pair_delete_policy(&self, _id: i64, _policy: DeletePolicy) -> Result<ReplicationForumPair, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
+
+        diesel::update(replications_forum_pairs.find(_id))
+            .set(on_message_delete.eq(_policy.as_str()))
             .get_result(&self.connection)
-            .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationPair"))
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationPair"))
     }
 
-    pub fn get_replication_reply(&self, _guild_id: i64, _channel_id: i64) -> Result<ReplicationReply, AppError> {
-        use crate::schema::replications_reply::dsl::*;
+    pub fn update_replication_forum_pair_attachment_fallback(&self, _id: i64, _fallback: AttachmentFallback) -> Result<ReplicationForumPair, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
 
-        replications_reply
-            .filter(guild_id.eq(_guild_id).and(channel_id.eq(_channel_id)))
-            .first(&self.connection)
-            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationReply"))
+        diesel::update(replications_forum_pairs.find(_id))
+            .set(attachment_fallback.eq(_fallback.as_str()))
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationPair"))
     }
 
-    pub fn get_replication_reply_full(&self, _guild_id: i64, _channel_id: i64, _message_id: i64) -> Result<ReplicationReply, AppError> {
-        use crate::schema::replications_reply::dsl::*;
+    pub fn update_replication_forum_pair_use_webhook(&self, _id: i64, _use_webhook: bool) -> Result<ReplicationForumPair, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
 
-        replications_reply
-            .filter(guild_id.eq(_guild_id).and(channel_id.eq(_channel_id)).and(message_id.eq(_message_id)))
-            .first(&self.connection)
-            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationReply"))
+        diesel::update(replications_forum_pairs.find(_id))
+            .set(use_webhook.eq(_use_webhook))
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationPair"))
     }
 
-    pub fn create_replication_reply(&self, dto: ReplicationReplyData) -> Result<ReplicationReply, AppError> {
-        diesel::insert_into(replications_reply::table)
-            .values(&dto)
+    pub fn update_replication_forum_pair_mirror_reactions(&self, _id: i64, _mirror_reactions: bool) -> Result<ReplicationForumPair, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
+
+        diesel::update(replications_forum_pairs.find(_id))
+            .set(mirror_reactions.eq(_mirror_reactions))
             .get_result(&self.connection)
-            .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationReply"))
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationPair"))
     }
 
-    pub fn delete_replication_reply(&self, _id: i64) -> Result<usize, AppError> {
-        use crate::schema::replications_reply::dsl::*;
+    pub fn update_replication_forum_pair_expiry_policy(&self, _id: i64, _policy: ExpiryPolicy) -> Result<ReplicationForumPair, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
 
-        diesel::delete(replications_reply.filter(id.eq(_id)))
-            .execute(&self.connection)
-            .map_err(|err| AppError::from_diesel_err(err, "while deleting convertionrate"))
+        diesel::update(replications_forum_pairs.find(_id))
+            .set(on_prompt_expiry.eq(_policy.as_str()))
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationPair"))
     }
 
-    pub fn update_replication_reply_status(&self, _guild_id: i64, _channel_id: i64, _responded: bool, _status: String) -> Result<ReplicationReply, AppError> {
-        use crate::schema::replications_reply::dsl::*;
+    pub fn update_replication_forum_pair_approval_policy(&self, _id: i64, _policy: ApprovalPolicy) -> Result<ReplicationForumPair, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
 
-        let updated = diesel::update(replications_reply.filter(guild_id.eq(_guild_id).and(channel_id.eq(_channel_id))))
-            .set((
-                responded.eq(_responded),
-                status.eq(_status),
-            ))
-            .execute(&self.connection)
-            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationReply"))?;
+        diesel::update(replications_forum_pairs.find(_id))
+            .set(approval_policy.eq(_policy.as_str()))
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationPair"))
+    }
 
-        if updated == 0 {
-            return Err(AppError::new("ReplicationReply not found", ErrorType::NotFound));
-        }
+    pub fn update_replication_forum_pair_thread_sync(&self, _id: i64, _sync: ThreadSync) -> Result<ReplicationForumPair, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
+
+        diesel::update(replications_forum_pairs.find(_id))
+            .set(thread_sync.eq(_sync.as_str()))
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationPair"))
+    }
+
+    pub fn update_replication_forum_pair_origin_label(&self, _id: i64, _label: OriginLabel) -> Result<ReplicationForumPair, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
+
+        diesel::update(replications_forum_pairs.find(_id))
+            .set(origin_label.eq(_label.as_str()))
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationPair"))
+    }
+
+    /// Flags every healthy pair with a side on `_forum_id` as broken, returns the flagged pairs.
+    pub fn mark_replication_forum_pairs_broken_by_forum(&self, _forum_id: i64, _reason: String) -> Result<Vec<ReplicationForumPair>, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
+
+        diesel::update(replications_forum_pairs.filter(from_forum.eq(_forum_id).or(to_forum.eq(_forum_id)).and(broken_reason.is_null())))
+            .set(broken_reason.eq(Some(_reason)))
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationPair"))
+    }
+
+    /// Flags every healthy pair with a side in `_guild_id` as broken, returns the flagged pairs.
+    pub fn mark_replication_forum_pairs_broken_by_guild(&self, _guild_id: i64, _reason: String) -> Result<Vec<ReplicationForumPair>, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
+
+        diesel::update(replications_forum_pairs.filter(from_guild.eq(_guild_id).or(to_guild.eq(_guild_id)).and(broken_reason.is_null())))
+            .set(broken_reason.eq(Some(_reason)))
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationPair"))
+    }
+
+    pub fn update_replication_forum_pair_broken_reason(&self, _id: i64, _reason: Option<String>) -> Result<ReplicationForumPair, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
+
+        diesel::update(replications_forum_pairs.find(_id))
+            .set(broken_reason.eq(_reason))
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationPair"))
+    }
+
+    pub fn get_broken_replication_forum_pairs_by_guild(&self, _guild_id: i64) -> Result<Vec<ReplicationForumPair>, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
+
+        replications_forum_pairs
+            .filter(from_guild.eq(_guild_id).or(to_guild.eq(_guild_id)))
+            .filter(broken_reason.is_not_null())
+            .order(id.asc())
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationPair"))
+    }
+
+    /// Forum pair that produced the thread pair `from_thread` -> `to_thread`.
+    pub fn get_replication_forum_pair_for_threads(&self, _from_thread: i64, _to_thread: i64) -> Result<ReplicationForumPair, AppError> {
+        replication_thread_pairs::table
+            .inner_join(replications_reply::table.inner_join(replications_forum_pairs::table))
+            .filter(replication_thread_pairs::from_thread.eq(_from_thread).and(replication_thread_pairs::to_thread.eq(_to_thread)))
+            .select(replications_forum_pairs::all_columns)
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationPair"))
+    }
+
+    pub fn get_replication_forum_pairs_by_guild(&self, _guild_id: i64) -> Result<Vec<ReplicationForumPair>, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
+
+        replications_forum_pairs
+            .filter(from_guild.eq(_guild_id).or(to_guild.eq(_guild_id)))
+            .order(id.asc())
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationPair"))
+    }
+
+    /// Deletes a forum pair. With `_cascade`, its replications_reply rows go first, taking their
+    /// replication_thread_pairs with them; otherwise a pair still in use is refused.
+    pub fn delete_replication_forum_pair(&self, _id: i64, _cascade: bool) -> Result<usize, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
+
+        self.connection.transaction(|| {
+            if _cascade {
+                diesel::delete(replications_reply::table.filter(replications_reply::replication_pairs.eq(_id)))
+                    .execute(&self.connection)?;
+            }
+
+            diesel::delete(replications_forum_pairs.find(_id))
+                .execute(&self.connection)
+        })
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting ReplicationPair"))
+    }
+
+    pub fn update_replication_forum_pair(&self, _id: i64, dto: ReplicationForumPairData) -> Result<ReplicationForumPair, AppError> {
+        use crate::schema::replications_forum_pairs::dsl::*;
+
+        diesel::update(replications_forum_pairs.find(_id))
+            .set(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationPair"))
+    }
+
+    pub fn create_replication_forum_pair(&self, dto: ReplicationForumPairData) -> Result<ReplicationForumPair, AppError> {
+        diesel::insert_into(replications_forum_pairs::table)
+            .values(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationPair"))
+    }
+
+    pub fn get_replication_reply(&self, _guild_id: i64, _channel_id: i64) -> Result<ReplicationReply, AppError> {
+        use crate::schema::replications_reply::dsl::*;
+
+        replications_reply
+            .filter(guild_id.eq(_guild_id).and(channel_id.eq(_channel_id)))
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationReply"))
+    }
+
+    pub fn get_replication_reply_by_id(&self, _id: i64) -> Result<ReplicationReply, AppError> {
+        use crate::schema::replications_reply::dsl::*;
+
+        replications_reply
+            .find(_id)
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationReply"))
+    }
+
+    // Every target offered for a thread, with the forum pair it would replicate through.
+    pub fn get_replication_reply_targets(&self, _guild_id: i64, _channel_id: i64) -> Result<Vec<(ReplicationReply, ReplicationForumPair)>, AppError> {
+        replications_reply::table
+            .inner_join(replications_forum_pairs::table)
+            .filter(replications_reply::guild_id.eq(_guild_id).and(replications_reply::channel_id.eq(_channel_id)))
+            .order(replications_reply::id.asc())
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationReply"))
+    }
+
+    pub fn create_replication_reply(&self, dto: ReplicationReplyData) -> Result<ReplicationReply, AppError> {
+        self.connection.transaction(|| {
+            let reply: ReplicationReply = diesel::insert_into(replications_reply::table)
+                .values(&dto)
+                .get_result(&self.connection)?;
+
+            diesel::insert_into(replication_status_events::table)
+                .values(&ReplicationStatusEventData {
+                    replication_reply_id: reply.id,
+                    from_status: None,
+                    to_status: reply.status.clone(),
+                    actor_id: None,
+                    reason: None,
+                })
+                .execute(&self.connection)?;
+
+            Ok(reply)
+        })
+            .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationReply"))
+    }
+
+    /// Moves a replication request to `_next` and records the change in `replication_status_events`,
+    /// refusing transitions that `ReplicationStatus::can_transition_to` does not allow.
+    pub fn transition_replication_reply(&self, _id: i64, _next: ReplicationStatus, _actor_id: Option<i64>, _reason: Option<String>) -> Result<ReplicationReply, AppError> {
+        use crate::schema::replications_reply::dsl::*;
+
+        self.connection.transaction::<_, AppError, _>(|| {
+            let current: ReplicationReply = replications_reply
+                .find(_id)
+                .for_update()
+                .first(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationReply"))?;
+
+            if !current.current_status().can_transition_to(_next) {
+                return Err(AppError::new(
+                    format!("Replication request {} cannot go from {} to {}", _id, current.status, _next.as_str()).as_str(),
+                    ErrorType::Validation,
+                ));
+            }
+
+            let updated: ReplicationReply = diesel::update(replications_reply.find(_id))
+                .set(status.eq(_next.as_str()))
+                .get_result(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationReply"))?;
+
+            diesel::insert_into(replication_status_events::table)
+                .values(&ReplicationStatusEventData {
+                    replication_reply_id: _id,
+                    from_status: Some(current.status),
+                    to_status: updated.status.clone(),
+                    actor_id: _actor_id,
+                    reason: _reason,
+                })
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationStatusEvent"))?;
+
+            Ok(updated)
+        })
+    }
+
+    // Latest status changes of the replication requests of a forum pair, newest first.
+    pub fn get_replication_status_events_for_pair(&self, _pair_id: i64, _limit: i64) -> Result<Vec<(ReplicationStatusEvent, ReplicationReply)>, AppError> {
+        replication_status_events::table
+            .inner_join(replications_reply::table)
+            .filter(replications_reply::replication_pairs.eq(_pair_id))
+            .order(replication_status_events::id.desc())
+            .limit(_limit)
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationStatusEvent"))
+    }
+
+    // Prompts still unanswered `_minutes` after they were sent, with the forum pair of each target.
+    pub fn get_pending_replication_replies(&self, _minutes: i32) -> Result<Vec<(ReplicationReply, ReplicationForumPair)>, AppError> {
+        use diesel::dsl::{now, IntervalDsl};
+
+        replications_reply::table
+            .inner_join(replications_forum_pairs::table)
+            .filter(replications_reply::status.eq(ReplicationStatus::Pending.as_str()).and(replications_reply::created_at.lt(now - _minutes.minutes())))
+            .order((replications_reply::channel_id.asc(), replications_reply::id.asc()))
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationReply"))
+    }
+
+    pub fn update_replication_reply_reminded_at(&self, _guild_id: i64, _channel_id: i64) -> Result<usize, AppError> {
+        use crate::schema::replications_reply::dsl::*;
+        use diesel::dsl::now;
 
-        self.get_replication_reply(_guild_id, _channel_id)
+        diesel::update(replications_reply.filter(guild_id.eq(_guild_id).and(channel_id.eq(_channel_id))))
+            .set(reminded_at.eq(now.nullable()))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationReply"))
     }
 
-    pub fn update_replication_reply_message_id(&self, _guild_id: i64, _channel_id: i64, _message_id: Option<i64>) -> Result<ReplicationReply, AppError> {
+    pub fn update_replication_reply_message_id(&self, _ids: &[i64], _message_id: Option<i64>) -> Result<usize, AppError> {
         use crate::schema::replications_reply::dsl::*;
 
-        let updated = diesel::update(replications_reply.filter(guild_id.eq(_guild_id).and(channel_id.eq(_channel_id))))
+        let updated = diesel::update(replications_reply.filter(id.eq_any(_ids)))
             .set((
                 message_id.eq(_message_id),
             ))
@@ -171,18 +938,67 @@ impl DBAccessManager {
             return Err(AppError::new("ReplicationReply not found", ErrorType::NotFound));
         }
 
-        self.get_replication_reply(_guild_id, _channel_id)
+        Ok(updated)
     }
 
     pub fn get_replication_thread_pairs(&self, _guild_id: i64, _thread_id: i64) -> Result<Vec<ReplicationThreadPair>, AppError> {
         use crate::schema::replication_thread_pairs::dsl::*;
 
+        // Only threads whose replication is accepted carry messages over
+        replication_thread_pairs
+            .inner_join(replications_reply::table)
+            .filter(from_guild.eq(_guild_id).and(from_thread.eq(_thread_id)))
+            .filter(replications_reply::status.eq(ReplicationStatus::Accepted.as_str()))
+            .select(crate::schema::replication_thread_pairs::all_columns)
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationPair"))
+    }
+
+    // Pairs leaving `_thread_id` whose replication is accepted or paused, a paused copy stops
+    // receiving messages but still follows the thread itself.
+    pub fn get_live_replication_thread_pairs(&self, _guild_id: i64, _thread_id: i64) -> Result<Vec<ReplicationThreadPair>, AppError> {
+        use crate::schema::replication_thread_pairs::dsl::*;
+
+        let live = [ReplicationStatus::Accepted.as_str(), ReplicationStatus::Paused.as_str()];
         replication_thread_pairs
+            .inner_join(replications_reply::table)
             .filter(from_guild.eq(_guild_id).and(from_thread.eq(_thread_id)))
+            .filter(replications_reply::status.eq_any(&live[..]))
+            .select(crate::schema::replication_thread_pairs::all_columns)
             .get_results(&self.connection)
             .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationPair"))
     }
 
+    // Every pair leaving `_thread_id`, whatever the status of its replication.
+    pub fn get_all_replication_thread_pairs(&self, _thread_id: i64) -> Result<Vec<ReplicationThreadPair>, AppError> {
+        use crate::schema::replication_thread_pairs::dsl::*;
+
+        replication_thread_pairs
+            .filter(from_thread.eq(_thread_id))
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationPair"))
+    }
+
+    /// Moves the replication requests of the pairs leaving `_thread_id` in `_guild_id` to `_next`.
+    /// Pairs are found whatever their status, a paused thread is not returned by
+    /// `get_replication_thread_pairs`. Returns the target thread of each pair with its outcome.
+    pub fn transition_thread_replication(&self, _guild_id: i64, _thread_id: i64, _next: ReplicationStatus, _actor_id: Option<i64>, _reason: Option<String>) -> Result<Vec<ThreadTransition>, AppError> {
+        Ok(self.get_all_replication_thread_pairs(_thread_id)?
+            .into_iter()
+            .filter(|pair| pair.from_guild == _guild_id)
+            .map(|pair| (pair.to_thread, self.transition_replication_reply(pair.replication_reply_id, _next, _actor_id, _reason.clone())))
+            .collect())
+    }
+
+    // Removes both directions of every pair involving `_thread_id`.
+    pub fn delete_replication_thread_pairs(&self, _thread_id: i64) -> Result<usize, AppError> {
+        use crate::schema::replication_thread_pairs::dsl::*;
+
+        diesel::delete(replication_thread_pairs.filter(from_thread.eq(_thread_id).or(to_thread.eq(_thread_id))))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting ReplicationPair"))
+    }
+
     pub fn create_replication_thread_pair(&self, dto: ReplicationThreadPairData) -> Result<ReplicationThreadPair, AppError> {
         diesel::insert_into(replication_thread_pairs::table)
             .values(&dto)
@@ -190,18 +1006,560 @@ impl DBAccessManager {
             .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationPair"))
     }
 
-    pub fn get_parent_forum_from_message_id(&self, _guild_id: i64, _message_id: i64) -> Result<i64, AppError> {
-        use crate::schema::replications_reply::dsl::*;
+    // Text channels `_channel_id` is mirrored into.
+    pub fn get_replication_channel_pairs(&self, _guild_id: i64, _channel_id: i64) -> Result<Vec<ReplicationChannelPair>, AppError> {
+        use crate::schema::replication_channel_pairs::dsl::*;
 
-        replications_reply
-            .filter(guild_id.eq(_guild_id).and(message_id.eq(_message_id)))
+        replication_channel_pairs
+            .filter(from_guild.eq(_guild_id).and(from_channel.eq(_channel_id)))
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationChannelPair"))
+    }
+
+    pub fn get_replication_channel_pairs_by_guild(&self, _guild_id: i64) -> Result<Vec<ReplicationChannelPair>, AppError> {
+        use crate::schema::replication_channel_pairs::dsl::*;
+
+        replication_channel_pairs
+            .filter(from_guild.eq(_guild_id).or(to_guild.eq(_guild_id)))
+            .order(id.asc())
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationChannelPair"))
+    }
+
+    pub fn get_replication_channel_pair_by_id(&self, _id: i64) -> Result<ReplicationChannelPair, AppError> {
+        use crate::schema::replication_channel_pairs::dsl::*;
+
+        replication_channel_pairs
+            .find(_id)
             .first(&self.connection)
-            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationReply"))
-            .and_then(|reply: ReplicationReply| {
-                self.get_replication_forum_pair_by_id(reply.replication_pairs)
-                    .map(|pair| {
-                        pair.from_forum
-                    })
-            })
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationChannelPair"))
+    }
+
+    pub fn create_replication_channel_pair(&self, dto: ReplicationChannelPairData) -> Result<ReplicationChannelPair, AppError> {
+        diesel::insert_into(replication_channel_pairs::table)
+            .values(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationChannelPair"))
+    }
+
+    pub fn delete_replication_channel_pair(&self, _id: i64) -> Result<usize, AppError> {
+        use crate::schema::replication_channel_pairs::dsl::*;
+
+        diesel::delete(replication_channel_pairs.find(_id))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting ReplicationChannelPair"))
+    }
+
+    pub fn create_replication_mirror(&self, dto: ReplicationMirrorData) -> Result<ReplicationMirror, AppError> {
+        diesel::insert_into(replication_mirrors::table)
+            .values(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationMirror"))
+    }
+
+    pub fn get_replication_mirror_by_id(&self, _id: i64) -> Result<ReplicationMirror, AppError> {
+        use crate::schema::replication_mirrors::dsl::*;
+
+        replication_mirrors
+            .find(_id)
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationMirror"))
+    }
+
+    // Mirrors reading from `_guild_id`, as needed when one of its channels is created.
+    pub fn get_replication_mirrors_from_guild(&self, _guild_id: i64) -> Result<Vec<ReplicationMirror>, AppError> {
+        use crate::schema::replication_mirrors::dsl::*;
+
+        replication_mirrors
+            .filter(from_guild.eq(_guild_id))
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationMirror"))
+    }
+
+    pub fn get_replication_mirrors_by_guild(&self, _guild_id: i64) -> Result<Vec<ReplicationMirror>, AppError> {
+        use crate::schema::replication_mirrors::dsl::*;
+
+        replication_mirrors
+            .filter(from_guild.eq(_guild_id).or(to_guild.eq(_guild_id)))
+            .order(id.asc())
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationMirror"))
+    }
+
+    /// Deletes a mirror and its channel mapping. The pairs it created stay, `unlink` removes them.
+    pub fn delete_replication_mirror(&self, _id: i64) -> Result<usize, AppError> {
+        use crate::schema::replication_mirrors::dsl::*;
+
+        diesel::delete(replication_mirrors.find(_id))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting ReplicationMirror"))
+    }
+
+    pub fn get_replication_mirror_channels(&self, _mirror_id: i64) -> Result<Vec<ReplicationMirrorChannel>, AppError> {
+        use crate::schema::replication_mirror_channels::dsl::*;
+
+        replication_mirror_channels
+            .filter(mirror_id.eq(_mirror_id))
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationMirrorChannel"))
+    }
+
+    pub fn create_replication_mirror_channel(&self, dto: ReplicationMirrorChannelData) -> Result<ReplicationMirrorChannel, AppError> {
+        diesel::insert_into(replication_mirror_channels::table)
+            .values(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationMirrorChannel"))
+    }
+
+    pub fn get_replication_filters(&self, _pair_id: i64) -> Result<Vec<ReplicationFilter>, AppError> {
+        use crate::schema::replication_filters::dsl::*;
+
+        replication_filters
+            .filter(pair_id.eq(_pair_id))
+            .order(id.asc())
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationFilter"))
+    }
+
+    pub fn get_replication_filter_by_id(&self, _id: i64) -> Result<ReplicationFilter, AppError> {
+        use crate::schema::replication_filters::dsl::*;
+
+        replication_filters
+            .find(_id)
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationFilter"))
+    }
+
+    pub fn create_replication_filter(&self, dto: ReplicationFilterData) -> Result<ReplicationFilter, AppError> {
+        diesel::insert_into(replication_filters::table)
+            .values(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationFilter"))
+    }
+
+    pub fn delete_replication_filter(&self, _id: i64) -> Result<usize, AppError> {
+        use crate::schema::replication_filters::dsl::*;
+
+        diesel::delete(replication_filters.find(_id))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting ReplicationFilter"))
+    }
+
+    pub fn create_replication_message_pair(&self, dto: ReplicationMessagePairData) -> Result<ReplicationMessagePair, AppError> {
+        diesel::insert_into(replication_message_pairs::table)
+            .values(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationMessagePair"))
+    }
+
+    pub fn get_replication_message_pairs(&self, _channel_id: i64, _message_id: i64) -> Result<Vec<ReplicationMessagePair>, AppError> {
+        use crate::schema::replication_message_pairs::dsl::*;
+
+        replication_message_pairs
+            .filter(from_channel.eq(_channel_id).and(from_message.eq(_message_id)))
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationMessagePair"))
+    }
+
+    // The pair that produced `_message_id`, when it is a replicated copy.
+    pub fn get_replication_message_source(&self, _channel_id: i64, _message_id: i64) -> Result<Option<ReplicationMessagePair>, AppError> {
+        use crate::schema::replication_message_pairs::dsl::*;
+
+        replication_message_pairs
+            .filter(to_channel.eq(_channel_id).and(to_message.eq(_message_id)))
+            .first(&self.connection)
+            .optional()
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationMessagePair"))
+    }
+
+    // Every copy of an origin message, however many hops away.
+    pub fn get_replication_message_copies(&self, _origin_channel: i64, _origin_message: i64) -> Result<Vec<ReplicationMessagePair>, AppError> {
+        use crate::schema::replication_message_pairs::dsl::*;
+
+        replication_message_pairs
+            .filter(origin_channel.eq(_origin_channel).and(origin_message.eq(_origin_message)))
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationMessagePair"))
+    }
+
+    pub fn has_replication_message_copy(&self, _origin_channel: i64, _origin_message: i64, _to_channel: i64) -> Result<bool, AppError> {
+        use crate::schema::replication_message_pairs::dsl::*;
+
+        diesel::select(diesel::dsl::exists(replication_message_pairs
+            .filter(origin_channel.eq(_origin_channel).and(origin_message.eq(_origin_message)).and(to_channel.eq(_to_channel)))))
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationMessagePair"))
+    }
+
+    pub fn delete_replication_message_pairs(&self, _channel_id: i64, _message_id: i64) -> Result<usize, AppError> {
+        use crate::schema::replication_message_pairs::dsl::*;
+
+        diesel::delete(replication_message_pairs.filter(from_channel.eq(_channel_id).and(from_message.eq(_message_id))))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting ReplicationMessagePair"))
+    }
+
+    pub fn delete_replication_message_pair(&self, _id: i64) -> Result<usize, AppError> {
+        use crate::schema::replication_message_pairs::dsl::*;
+
+        diesel::delete(replication_message_pairs.find(_id))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting ReplicationMessagePair"))
+    }
+
+    pub fn get_replication_webhook(&self, _channel_id: i64) -> Result<ReplicationWebhook, AppError> {
+        use crate::schema::replication_webhooks::dsl::*;
+
+        replication_webhooks
+            .filter(channel_id.eq(_channel_id))
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationWebhook"))
+    }
+
+    pub fn get_replication_webhook_by_webhook_id(&self, _webhook_id: i64) -> Result<ReplicationWebhook, AppError> {
+        use crate::schema::replication_webhooks::dsl::*;
+
+        replication_webhooks
+            .filter(webhook_id.eq(_webhook_id))
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationWebhook"))
+    }
+
+    pub fn create_replication_webhook(&self, dto: ReplicationWebhookData) -> Result<ReplicationWebhook, AppError> {
+        diesel::insert_into(replication_webhooks::table)
+            .values(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationWebhook"))
+    }
+
+    pub fn delete_replication_webhook(&self, _channel_id: i64) -> Result<usize, AppError> {
+        use crate::schema::replication_webhooks::dsl::*;
+
+        diesel::delete(replication_webhooks.filter(channel_id.eq(_channel_id)))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting ReplicationWebhook"))
+    }
+
+    /// Message standing for `_message_id` of `_channel_id` in `_target_channel`, either its copy
+    /// or, when `_message_id` is itself a copy, the source it was replicated from.
+    pub fn get_replicated_counterpart(&self, _channel_id: i64, _message_id: i64, _target_channel: i64) -> Result<i64, AppError> {
+        use crate::schema::replication_message_pairs::dsl::*;
+
+        let forward = replication_message_pairs
+            .filter(from_channel.eq(_channel_id).and(from_message.eq(_message_id)).and(to_channel.eq(_target_channel)))
+            .select(to_message)
+            .first::<i64>(&self.connection)
+            .optional()
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationMessagePair"))?;
+
+        if let Some(found) = forward {
+            return Ok(found);
+        }
+
+        replication_message_pairs
+            .filter(to_channel.eq(_channel_id).and(to_message.eq(_message_id)).and(from_channel.eq(_target_channel)))
+            .select(from_message)
+            .first::<i64>(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationMessagePair"))
+    }
+
+    /// Every mapping row touching a message: its copies when it is a source, or its source and
+    /// the sibling copies when it is itself a copy.
+    pub fn get_linked_message_pairs(&self, _channel_id: i64, _message_id: i64) -> Result<Vec<ReplicationMessagePair>, AppError> {
+        use crate::schema::replication_message_pairs::dsl::*;
+
+        let source = replication_message_pairs
+            .filter(to_channel.eq(_channel_id).and(to_message.eq(_message_id)))
+            .select((from_channel, from_message))
+            .first::<(i64, i64)>(&self.connection)
+            .optional()
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationMessagePair"))?;
+
+        let (source_channel, source_message) = source.unwrap_or((_channel_id, _message_id));
+
+        replication_message_pairs
+            .filter(from_channel.eq(source_channel).and(from_message.eq(source_message)))
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationMessagePair"))
+    }
+
+    // Remote reactions represented by the bot on a message, in the order they first appeared.
+    pub fn get_replication_reaction_counts(&self, _channel_id: i64, _message_id: i64) -> Result<Vec<ReplicationReactionCount>, AppError> {
+        use crate::schema::replication_reaction_counts::dsl::*;
+
+        replication_reaction_counts
+            .filter(channel_id.eq(_channel_id).and(message_id.eq(_message_id)))
+            .order(id.asc())
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationReactionCount"))
+    }
+
+    /// Adds `_delta` remote reactions represented by the bot on a message, returns the new count.
+    pub fn add_replication_reaction_count(&self, _channel_id: i64, _message_id: i64, _emoji: String, _delta: i32) -> Result<i32, AppError> {
+        use crate::schema::replication_reaction_counts::dsl::*;
+
+        let dto = ReplicationReactionCountData {
+            channel_id: _channel_id,
+            message_id: _message_id,
+            emoji: _emoji,
+            count: _delta.max(0),
+        };
+
+        let updated: i32 = diesel::insert_into(replication_reaction_counts)
+            .values(&dto)
+            .on_conflict((channel_id, message_id, emoji))
+            .do_update()
+            .set(count.eq(count + _delta))
+            .returning(count)
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationReactionCount"))?;
+
+        if updated <= 0 {
+            diesel::delete(replication_reaction_counts.filter(channel_id.eq(_channel_id).and(message_id.eq(_message_id)).and(emoji.eq(dto.emoji))))
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while deleting ReplicationReactionCount"))?;
+        }
+
+        Ok(updated)
+    }
+
+    pub fn get_replication_moderation_channel(&self, _guild_id: i64) -> Result<Option<ReplicationModerationChannel>, AppError> {
+        use crate::schema::replication_moderation_channels::dsl::*;
+
+        replication_moderation_channels
+            .filter(guild_id.eq(_guild_id))
+            .first(&self.connection)
+            .optional()
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationModerationChannel"))
+    }
+
+    pub fn set_replication_moderation_channel(&self, dto: ReplicationModerationChannelData) -> Result<ReplicationModerationChannel, AppError> {
+        use crate::schema::replication_moderation_channels::dsl::*;
+
+        diesel::insert_into(replication_moderation_channels)
+            .values(&dto)
+            .on_conflict(guild_id)
+            .do_update()
+            .set(channel_id.eq(dto.channel_id))
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationModerationChannel"))
+    }
+
+    pub fn create_replication_queue_entry(&self, dto: ReplicationQueueEntryData) -> Result<ReplicationQueueEntry, AppError> {
+        diesel::insert_into(replication_queue::table)
+            .values(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationQueueEntry"))
+    }
+
+    pub fn update_replication_queue_message_id(&self, _id: i64, _message_id: i64) -> Result<ReplicationQueueEntry, AppError> {
+        use crate::schema::replication_queue::dsl::*;
+
+        diesel::update(replication_queue.find(_id))
+            .set(message_id.eq(Some(_message_id)))
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationQueueEntry"))
+    }
+
+    // A queued request with the reply it stands for and the pair it would replicate through.
+    pub fn get_replication_queue_entry(&self, _id: i64) -> Result<QueueRow, AppError> {
+        replication_queue::table
+            .inner_join(replications_reply::table.inner_join(replications_forum_pairs::table))
+            .filter(replication_queue::id.eq(_id))
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationQueueEntry"))
+    }
+
+    pub fn get_replication_queue_by_guild(&self, _guild_id: i64) -> Result<Vec<QueueRow>, AppError> {
+        replication_queue::table
+            .inner_join(replications_reply::table.inner_join(replications_forum_pairs::table))
+            .filter(replication_queue::guild_id.eq(_guild_id))
+            .order(replication_queue::id.asc())
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationQueueEntry"))
+    }
+
+    pub fn get_replication_queue_by_reply(&self, _replication_reply_id: i64) -> Result<Option<ReplicationQueueEntry>, AppError> {
+        use crate::schema::replication_queue::dsl::*;
+
+        replication_queue
+            .filter(replication_reply_id.eq(_replication_reply_id))
+            .first(&self.connection)
+            .optional()
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationQueueEntry"))
+    }
+
+    pub fn delete_replication_queue_entry(&self, _id: i64) -> Result<usize, AppError> {
+        use crate::schema::replication_queue::dsl::*;
+
+        diesel::delete(replication_queue.find(_id))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting ReplicationQueueEntry"))
+    }
+
+    pub fn create_replication_backfill_job(&self, dto: ReplicationBackfillJobData) -> Result<ReplicationBackfillJob, AppError> {
+        diesel::insert_into(replication_backfill_jobs::table)
+            .values(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while creating ReplicationBackfillJob"))
+    }
+
+    pub fn get_running_replication_backfill_jobs(&self) -> Result<Vec<ReplicationBackfillJob>, AppError> {
+        use crate::schema::replication_backfill_jobs::dsl::*;
+
+        replication_backfill_jobs
+            .filter(status.eq("running"))
+            .order(id.asc())
+            .get_results(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationBackfillJob"))
+    }
+
+    pub fn get_running_replication_backfill_job_for_threads(&self, _from_thread: i64, _to_thread: i64) -> Result<Option<ReplicationBackfillJob>, AppError> {
+        use crate::schema::replication_backfill_jobs::dsl::*;
+
+        replication_backfill_jobs
+            .filter(from_thread.eq(_from_thread).and(to_thread.eq(_to_thread)).and(status.eq("running")))
+            .first(&self.connection)
+            .optional()
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving ReplicationBackfillJob"))
+    }
+
+    pub fn update_replication_backfill_cursor(&self, _id: i64, _last_message: i64) -> Result<usize, AppError> {
+        use crate::schema::replication_backfill_jobs::dsl::*;
+
+        diesel::update(replication_backfill_jobs.find(_id))
+            .set(last_message.eq(Some(_last_message)))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationBackfillJob"))
+    }
+
+    pub fn finish_replication_backfill_job(&self, _id: i64, _error: Option<String>) -> Result<usize, AppError> {
+        use crate::schema::replication_backfill_jobs::dsl::*;
+
+        let _status = if _error.is_some() { "failed" } else { "done" };
+        diesel::update(replication_backfill_jobs.find(_id))
+            .set((status.eq(_status), error.eq(_error)))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating ReplicationBackfillJob"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATUSES: [ReplicationStatus; 6] = [
+        ReplicationStatus::Pending,
+        ReplicationStatus::Accepted,
+        ReplicationStatus::Declined,
+        ReplicationStatus::Expired,
+        ReplicationStatus::Paused,
+        ReplicationStatus::Revoked,
+    ];
+
+    #[test]
+    fn status_names_round_trip() {
+        for status in STATUSES {
+            assert_eq!(ReplicationStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(ReplicationStatus::parse("Accepted"), None);
+        assert_eq!(ReplicationStatus::parse(""), None);
+    }
+
+    #[test]
+    fn pending_requests_are_answered_once() {
+        use ReplicationStatus::*;
+
+        assert!(Pending.can_transition_to(Accepted));
+        assert!(Pending.can_transition_to(Declined));
+        assert!(Pending.can_transition_to(Expired));
+        assert!(!Pending.can_transition_to(Paused));
+        assert!(!Pending.can_transition_to(Revoked));
+        assert!(!Pending.can_transition_to(Pending));
+    }
+
+    #[test]
+    fn accepted_requests_can_be_paused_resumed_and_ended() {
+        use ReplicationStatus::*;
+
+        assert!(Accepted.can_transition_to(Paused));
+        assert!(Paused.can_transition_to(Accepted));
+        assert!(Accepted.can_transition_to(Revoked));
+        assert!(Paused.can_transition_to(Revoked));
+        assert!(Accepted.can_transition_to(Declined));
+        assert!(!Accepted.can_transition_to(Accepted));
+        assert!(!Paused.can_transition_to(Paused));
+        assert!(!Accepted.can_transition_to(Pending));
+        assert!(!Paused.can_transition_to(Declined));
+    }
+
+    #[test]
+    fn final_statuses_never_change() {
+        use ReplicationStatus::*;
+
+        for status in [Declined, Expired, Revoked] {
+            for next in STATUSES {
+                assert!(!status.can_transition_to(next), "{:?} -> {:?}", status, next);
+            }
+        }
+    }
+
+    // A migrated database at `TEST_DATABASE_URL`, in a transaction that is never committed.
+    // Tests that need one are skipped when it is not set.
+    fn test_access() -> Option<DBAccessManager> {
+        let url = std::env::var("TEST_DATABASE_URL").ok()?;
+        let access = DBAccessManager::new(crate::database::get_pg_pool_sized(&url, 1).get().ok()?);
+        access.connection.begin_test_transaction().ok()?;
+        Some(access)
+    }
+
+    #[test]
+    fn paused_threads_can_be_resumed() {
+        let db = match test_access() {
+            Some(db) => db,
+            None => return,
+        };
+
+        let pair = db.create_replication_forum_pair(ReplicationForumPairData { from_guild: 1, from_forum: 10, to_guild: 2, to_forum: 20 }).unwrap();
+        let reply = db.create_replication_reply(ReplicationReplyData {
+            status: ReplicationStatus::Accepted.as_str().to_string(),
+            guild_id: 1,
+            channel_id: 100,
+            replication_pairs: pair.id,
+            message_id: None,
+            message_owner: 1000,
+        }).unwrap();
+        for (source, target) in [((1, 100), (2, 200)), ((2, 200), (1, 100))] {
+            db.create_replication_thread_pair(ReplicationThreadPairData {
+                from_guild: source.0,
+                from_thread: source.1,
+                to_guild: target.0,
+                to_thread: target.1,
+                replication_reply_id: reply.id,
+            }).unwrap();
+        }
+
+        // Paused from the target side
+        let paused = db.transition_thread_replication(2, 200, ReplicationStatus::Paused, Some(1000), Some("maintenance".to_string())).unwrap();
+        assert_eq!(paused.len(), 1);
+        assert!(paused[0].1.is_ok());
+        assert!(db.get_replication_thread_pairs(1, 100).unwrap().is_empty());
+        assert!(db.get_replication_thread_pairs(2, 200).unwrap().is_empty());
+        // Still followed by renames, locks and archives
+        assert_eq!(db.get_live_replication_thread_pairs(1, 100).unwrap().len(), 1);
+
+        let resumed = db.transition_thread_replication(1, 100, ReplicationStatus::Accepted, Some(1000), None).unwrap();
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(resumed[0].0, 200);
+        assert!(resumed[0].1.is_ok());
+        assert_eq!(db.get_replication_thread_pairs(1, 100).unwrap().len(), 1);
+        assert_eq!(db.get_replication_reply_by_id(reply.id).unwrap().current_status(), ReplicationStatus::Accepted);
+
+        // Resuming twice is refused
+        let again = db.transition_thread_replication(1, 100, ReplicationStatus::Accepted, None, None).unwrap();
+        assert!(again[0].1.is_err());
+
+        // Pairs of another guild are left alone
+        assert!(db.transition_thread_replication(3, 100, ReplicationStatus::Paused, None, None).unwrap().is_empty());
     }
 }
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use regex::Regex;
+use serenity::all::{Context, Message, RoleId};
+use crate::handler::db_access::{FilterKind, ReplicationFilter};
+use crate::handler::Handler;
+
+const MAX_FILTER_LENGTH: usize = 255;
+
+// Whole words only, a multi-word value matches as a phrase.
+fn contains_word(content: &str, word: &str) -> bool {
+    let content = content.to_lowercase();
+    let word = word.to_lowercase();
+    if word.contains(char::is_whitespace) {
+        return content.contains(&word);
+    }
+
+    content.split(|c: char| !c.is_alphanumeric()).any(|token| token == word)
+}
+
+/// Why `msg` must not be forwarded, if a filter blocks it. Content and deny rules block on any
+/// match; when the pair has allow rules, the author or one of their roles must match one of them.
+/// `patterns` holds the compiled `regex` filters by pattern.
+pub fn filter_reason(filters: &[ReplicationFilter], msg: &Message, roles: &[RoleId], patterns: &HashMap<String, Option<Regex>>) -> Option<String> {
+    let author = msg.author.id.get().to_string();
+    let has_role = |value: &str| roles.iter().any(|role| role.get().to_string() == value);
+
+    for filter in filters {
+        let blocked = match filter.kind() {
+            Some(FilterKind::Prefix) => msg.content.starts_with(&filter.value),
+            Some(FilterKind::Word) => contains_word(&msg.content, &filter.value),
+            // Patterns are checked when added, one that no longer compiles is ignored
+            Some(FilterKind::Regex) => patterns.get(&filter.value).and_then(Option::as_ref).is_some_and(|pattern| pattern.is_match(&msg.content)),
+            Some(FilterKind::DenyAuthor) => filter.value == author,
+            Some(FilterKind::DenyRole) => has_role(&filter.value),
+            _ => false,
+        };
+        if blocked {
+            return Some(format!("{} `{}`", filter.kind, filter.value));
+        }
+    }
+
+    let allowed: Vec<&ReplicationFilter> = filters.iter()
+        .filter(|f| matches!(f.kind(), Some(FilterKind::AllowAuthor | FilterKind::AllowRole)))
+        .collect();
+    let is_allowed = allowed.iter().any(|f| match f.kind() {
+        Some(FilterKind::AllowAuthor) => f.value == author,
+        _ => has_role(&f.value),
+    });
+    if !allowed.is_empty() && !is_allowed {
+        return Some("author not in the allow list".to_string());
+    }
+
+    None
+}
+
+/// Checks a rule before it is stored: patterns compile and authors and roles are ids.
+pub fn validate_filter(kind: FilterKind, value: &str) -> Result<(), String> {
+    if value.is_empty() || value.len() > MAX_FILTER_LENGTH {
+        return Err(format!("The value must be between 1 and {} characters", MAX_FILTER_LENGTH));
+    }
+
+    match kind {
+        FilterKind::Regex => Regex::new(value).map(|_| ()).map_err(|err| format!("Invalid pattern: {}", err)),
+        FilterKind::AllowAuthor | FilterKind::DenyAuthor | FilterKind::AllowRole | FilterKind::DenyRole => match value.parse::<u64>() {
+            Ok(id) if id != 0 => Ok(()),
+            _ => Err(format!("`{}` is not an id", value)),
+        },
+        FilterKind::Word | FilterKind::Prefix => Ok(()),
+    }
+}
+
+impl Handler {
+    /// Applies the filters of forum pair `pair_id` to `msg`, posted in `guild_id`. Filters belong to
+    /// forum pairs only, text channel pairs replicate everything.
+    pub(crate) fn filtered(&self, ctx: &Context, guild_id: i64, msg: &Message, pair_id: i64) -> Option<String> {
+        let filters = self.get_access().and_then(|db| db.get_replication_filters(pair_id)).unwrap_or_default();
+        if filters.is_empty() {
+            return None;
+        }
+
+        // Messages read from the history carry no member, the cache may still know their roles
+        let roles = match &msg.member {
+            Some(member) => member.roles.clone(),
+            None => ctx.cache.guild(guild_id as u64)
+                .and_then(|g| g.members.get(&msg.author.id).map(|m| m.roles.clone()))
+                .unwrap_or_default(),
+        };
+
+        // Patterns are compiled once and shared by every pair using them
+        let mut patterns = self.filter_patterns.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for filter in filters.iter().filter(|f| f.kind() == Some(FilterKind::Regex)) {
+            patterns.entry(filter.value.clone()).or_insert_with(|| Regex::new(&filter.value).ok());
+        }
+
+        filter_reason(&filters, msg, &roles, &patterns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serenity::all::UserId;
+
+    fn filter(kind: FilterKind, value: &str) -> ReplicationFilter {
+        ReplicationFilter {
+            id: 1,
+            pair_id: 1,
+            kind: kind.as_str().to_string(),
+            value: value.to_string(),
+            created_at: Default::default(),
+        }
+    }
+
+    fn message(author: u64, content: &str) -> Message {
+        let mut msg = Message::default();
+        msg.author.id = UserId::new(author);
+        msg.content = content.to_string();
+        msg
+    }
+
+    fn patterns(filters: &[ReplicationFilter]) -> HashMap<String, Option<Regex>> {
+        filters.iter().map(|f| (f.value.clone(), Regex::new(&f.value).ok())).collect()
+    }
+
+    fn reason(filters: &[ReplicationFilter], msg: &Message, roles: &[RoleId]) -> Option<String> {
+        filter_reason(filters, msg, roles, &patterns(filters))
+    }
+
+    #[test]
+    fn words_match_whole_words_only() {
+        assert!(contains_word("Hello, World!", "world"));
+        assert!(!contains_word("worldwide news", "world"));
+        assert!(!contains_word("", "world"));
+    }
+
+    #[test]
+    fn multi_word_values_match_as_a_phrase() {
+        assert!(contains_word("Please buy now, thanks", "Buy Now"));
+        assert!(!contains_word("now buy", "buy now"));
+    }
+
+    #[test]
+    fn no_filter_lets_everything_through() {
+        assert_eq!(reason(&[], &message(1, "anything"), &[]), None);
+    }
+
+    #[test]
+    fn content_filters_block_on_match() {
+        let filters = [filter(FilterKind::Prefix, "!"), filter(FilterKind::Word, "spoiler"), filter(FilterKind::Regex, r"\d{4}")];
+
+        assert!(reason(&filters, &message(1, "!ping"), &[]).is_some());
+        assert!(reason(&filters, &message(1, "a Spoiler ahead"), &[]).is_some());
+        assert!(reason(&filters, &message(1, "code 1234"), &[]).is_some());
+        assert_eq!(reason(&filters, &message(1, "spoilers are fine, 123"), &[]), None);
+    }
+
+    #[test]
+    fn uncompiled_patterns_are_ignored() {
+        let filters = [filter(FilterKind::Regex, "(")];
+
+        assert_eq!(reason(&filters, &message(1, "("), &[]), None);
+        assert_eq!(filter_reason(&filters, &message(1, "("), &[], &HashMap::new()), None);
+    }
+
+    #[test]
+    fn deny_rules_win_over_allow_rules() {
+        let filters = [filter(FilterKind::AllowAuthor, "1"), filter(FilterKind::DenyRole, "10")];
+
+        assert_eq!(reason(&filters, &message(1, "hi"), &[]), None);
+        assert!(reason(&filters, &message(1, "hi"), &[RoleId::new(10)]).is_some());
+    }
+
+    #[test]
+    fn allow_rules_require_a_matching_author_or_role() {
+        let filters = [filter(FilterKind::AllowAuthor, "1"), filter(FilterKind::AllowRole, "20")];
+
+        assert_eq!(reason(&filters, &message(1, "hi"), &[]), None);
+        assert_eq!(reason(&filters, &message(2, "hi"), &[RoleId::new(20)]), None);
+        assert_eq!(reason(&filters, &message(2, "hi"), &[RoleId::new(21)]), Some("author not in the allow list".to_string()));
+    }
+
+    #[test]
+    fn validate_filter_checks_patterns_and_ids() {
+        assert!(validate_filter(FilterKind::Regex, r"^\w+$").is_ok());
+        assert!(validate_filter(FilterKind::Regex, "(").is_err());
+        assert!(validate_filter(FilterKind::DenyAuthor, "1234").is_ok());
+        assert!(validate_filter(FilterKind::DenyAuthor, "0").is_err());
+        assert!(validate_filter(FilterKind::AllowRole, "-5").is_err());
+        assert!(validate_filter(FilterKind::AllowRole, "moderators").is_err());
+        assert!(validate_filter(FilterKind::Word, "").is_err());
+        assert!(validate_filter(FilterKind::Prefix, &"x".repeat(MAX_FILTER_LENGTH + 1)).is_err());
+        assert!(validate_filter(FilterKind::Prefix, "!").is_ok());
+    }
+}
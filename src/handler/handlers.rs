@@ -1,23 +1,37 @@
-use std::num::NonZeroU64;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use serde::Serialize;
-use serenity::all::{CreateMessage, Guild, GuildId, PartialGuildChannel};
+use serenity::all::{CreateMessage, EditMessage, EditWebhookMessage, Guild, GuildId, Interaction, MessageUpdateEvent, PartialGuildChannel, UnavailableGuild};
 use serenity::all::{CacheHttp, ChannelId, ChannelType, Context, EventHandler, GuildChannel, Message, MessageId, Reaction, Ready};
 use serenity::async_trait;
-use serenity::builder::{CreateChannel, CreateForumPost, CreateThread};
 use crate::DbHandler;
-use crate::handler::db_access::{ReplicationReplyData, ReplicationThreadPairData};
+use crate::handler::moderation::is_moderation_component;
+use crate::handler::slash_commands::guild_name;
+use crate::handler::prompts::{is_prompt_component, prompt_components, prompt_content, PromptAnswer};
+use crate::handler::content::no_mentions;
+use crate::handler::attachments::{oversized_lines, upload_limit, with_fallback_lines};
+use crate::handler::db_access::{ApprovalPolicy, AttachmentFallback, DeletePolicy, ReplicationMessagePair, ReplicationReplyData, ReplicationStatus};
+use crate::errors::{AppError, ErrorType};
 use crate::handler::Handler;
 use crate::log::{write_error_log, write_info_log};
 
-const UP_EMOJI: char = '👍';
-const DOWN_EMOJI: char = '👎';
 const ROCKET_EMOJI: char = '🚀';
 const BOMB_EXPLODED_EMOJI: char = '💥';
+const FILTERED_EMOJI: char = '🚫';
+
+pub fn replicated_content(author_name: &str, content: &str) -> String {
+    format!("`{}`: {}", author_name, content)
+}
 
 impl Handler {
     pub fn new(pool: Arc<DbHandler>) -> Self {
-        crate::handler::Handler { pool }
+        crate::handler::Handler {
+            pool,
+            webhooks: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            scheduler_started: Arc::new(AtomicBool::new(false)),
+            filter_patterns: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        }
     }
 
     pub fn get_access(&self) -> Result<crate::database::DBAccessManager, crate::errors::AppError> {
@@ -29,12 +43,101 @@ impl Handler {
             }),
         }
     }
+
+    // Deletes one copy and forgets it, its own copies follow through `message_delete`.
+    async fn delete_copy(&self, ctx: &Context, r: &ReplicationMessagePair) -> Result<(), AppError> {
+        let distant_channel = ChannelId::new(r.to_channel as u64);
+        let distant_message = MessageId::new(r.to_message as u64);
+
+        let deleted = match r.webhook_id {
+            Some(webhook_id) => self.webhook_by_id(ctx, webhook_id).await?
+                .delete_message(&ctx.http, Some(distant_channel), distant_message).await,
+            None => distant_channel.delete_message(&ctx.http, distant_message).await,
+        };
+        deleted.map_err(|why| AppError::new(format!("{why:?}").as_str(), ErrorType::DistantServer))?;
+
+        self.get_access()?.delete_replication_message_pair(r.id)?;
+        Ok(())
+    }
+
+    // Apply the forum pair's delete policy to every copy of a deleted source message.
+    async fn replicate_deletion(&self, ctx: &Context, channel_id: ChannelId, message_id: MessageId) {
+        let _db_access = match self.get_access() {
+            Ok(access) => access,
+            Err(err) => {
+                write_error_log(err.message);
+                return;
+            }
+        };
+
+        let replicated = match _db_access.get_replication_message_pairs(channel_id.get() as i64, message_id.get() as i64) {
+            Ok(replicated) => replicated,
+            Err(err) => {
+                write_error_log(format!("Error getting replicated messages: {}", err.message));
+                return;
+            }
+        };
+
+        for r in replicated.iter() {
+            let policy = _db_access.get_replication_forum_pair_for_threads(r.from_channel, r.to_channel)
+                .map(|pair| pair.delete_policy())
+                .unwrap_or(DeletePolicy::Delete);
+
+            let distant_channel = ChannelId::new(r.to_channel as u64);
+            let distant_message = MessageId::new(r.to_message as u64);
+
+            let webhook = match r.webhook_id {
+                Some(webhook_id) => match self.webhook_by_id(ctx, webhook_id).await {
+                    Ok(webhook) => Some(webhook),
+                    Err(err) => {
+                        write_error_log(format!("Error getting webhook for {}: {}", distant_message, err.message));
+                        continue;
+                    }
+                },
+                None => None,
+            };
+
+            let result = match (policy, webhook) {
+                (DeletePolicy::Delete, Some(webhook)) => webhook.delete_message(&ctx.http, Some(distant_channel), distant_message).await,
+                (DeletePolicy::Delete, None) => distant_channel.delete_message(&ctx.http, distant_message).await,
+                (DeletePolicy::Mark, Some(webhook)) => match webhook.get_message(&ctx.http, Some(distant_channel), distant_message).await {
+                    Ok(distant) => {
+                        let edit = EditWebhookMessage::new().content(format!("~~{}~~ *(deleted)*", distant.content)).in_thread(distant_channel);
+                        webhook.edit_message(&ctx.http, distant_message, edit).await.map(|_| ())
+                    }
+                    Err(why) => Err(why),
+                },
+                (DeletePolicy::Mark, None) => match distant_channel.message(&ctx.http, distant_message).await {
+                    Ok(distant) => {
+                        let edit = EditMessage::new().content(format!("~~{}~~ *(deleted)*", distant.content));
+                        distant_channel.edit_message(&ctx.http, distant_message, edit).await.map(|_| ())
+                    }
+                    Err(why) => Err(why),
+                },
+                (DeletePolicy::Keep, _) => Ok(()),
+            };
+
+            match result {
+                Ok(_) => {
+                    write_info_log(format!("Replicated deletion of {} to {} in {} ({})", message_id, distant_message, distant_channel, policy.as_str()));
+                }
+                Err(why) => {
+                    write_error_log(format!("Error replicating deletion of {}: {why:?}", distant_message));
+                }
+            }
+        }
+
+        if !replicated.is_empty() {
+            let _ = _db_access.delete_replication_message_pairs(channel_id.get() as i64, message_id.get() as i64);
+        }
+    }
 }
 
 #[async_trait]
 impl EventHandler for crate::handler::Handler {
-    async fn cache_ready(&self, ctx: Context, _: Vec<GuildId>) {
-        write_info_log(format!("{} unknown members", ctx.cache.unknown_members()));
+
+    async fn channel_create(&self, ctx: Context, channel: GuildChannel) {
+        self.mirror_new_channel(&ctx, &channel).await;
     }
 
     async fn channel_delete(&self, _ctx: Context, channel: GuildChannel, messages: Option<Vec<Message>>) {
@@ -48,6 +151,19 @@ impl EventHandler for crate::handler::Handler {
                 write_info_log(format!("Channel deleted: {} in server id {}", channel.name, channel.guild_id));
             }
         }
+
+        if channel.kind == ChannelType::Forum {
+            self.forum_deleted(&_ctx, channel.guild_id, channel.id, &channel.name).await;
+        }
+    }
+
+    // `unavailable` is set on outages, the guild is only gone when the bot was removed from it
+    async fn guild_delete(&self, ctx: Context, incomplete: UnavailableGuild, _full: Option<Guild>) {
+        if incomplete.unavailable {
+            return;
+        }
+
+        self.guild_removed(&ctx, incomplete.id).await;
     }
 
     // Set a handler for the `message` event. This is called whenever a new message is received.
@@ -55,233 +171,175 @@ impl EventHandler for crate::handler::Handler {
     // Event handlers are dispatched through a threadpool, and so multiple events can be
     // dispatched simultaneously.
     async fn message(&self, ctx: Context, msg: Message) {
-        if msg.author.bot {
-            return;
-        }
-
         write_info_log(format!("MESSAGE: {}: {}", msg.author.name, msg.content));
         write_info_log(format!("New message in {:?} from {}", msg.channel_id, msg.author.name));
 
-        let _db_access = match self.pool.pool.get() {
-            Ok(conn) => crate::database::DBAccessManager::new(conn),
+        // Copies are forwarded along the next pairs as soon as they are sent, see `forward_copies`,
+        // and prompts and notes of the bot stay where they are
+        if self.produced_by_bot(&ctx, &msg) {
+            return;
+        }
+
+        let guild_id = msg.guild_id.unwrap_or_default().get() as i64;
+        let forwarded = match self.forward_message(&ctx, guild_id, &msg).await {
+            Ok(forwarded) => forwarded,
             Err(err) => {
-                write_error_log(format!("Error getting connection from pool: {}", err.to_string()));
+                write_error_log(format!("Error replicating message {}: {}", msg.id, err.message));
                 return;
             }
         };
 
-        // let message_channgel_url = format!("https://discord.com/channels/{}/{}", msg.guild_id.unwrap_or_default(), msg.channel_id);
-        // msg.channel_id.say(&ctx.http, format!("New message in {:?} from {} -> {}", msg.channel_id, msg.author.name, message_channgel_url)).await;
-
-        match _db_access.get_replication_thread_pairs(msg.guild_id.unwrap_or_default().get() as i64, msg.channel_id.get() as i64) {
-            Ok(found) => {
-                // let _ = msg.channel_id.say(&ctx.http, format!("Replicated message: {:?} -> {:?}", msg.content, found)).await;
-                // let _ = msg.channel_id.say(&ctx.http, format!("Guid {} channel id {}", msg.guild_id.clone().unwrap_or_default(), msg.channel_id)).await;
-
-                let message_owner_name = msg.author.name.clone();
-                let mut message_without_quotes = msg.content.clone();
-                // message_without_quotes.remove(0);
-                // message_without_quotes.pop();
-
-                for f in found {
-                    // ctx.http.create_message(f.to_channel).content(format!("Replicated message: {:?}", msg.content)).await;
-                    // msg.channel_id.say(&ctx.http, format!("Replicating to guild {} channel {}", f.to_guild, f.to_thread)).await;
-                    // let distant_url = format!("https://discord.com/channels/{}/{}", f.to_guild, f.to_thread);
-                    // msg.channel_id.say(&ctx.http, format!("Distant URL: {}", distant_url)).await;
-
-                    let as_nonzerou64_guild = f.to_guild as u64;
-
-                    let guild = match ctx.cache.guild(as_nonzerou64_guild) {
-                        Some(guild) => guild.clone(),
-                        None => {
-                            write_error_log(format!("Guild not found: {}", f.to_guild));
-                            return;
-                        }
-                    };
-                    let u64_thread = f.to_thread as u64;
-                    // let as_nonzerou64_channel = NonZeroU64::new(u64_thread).unwrap();
-                    // let threa: ChannelId = as_nonzerou64_channel.into();
-
-                    let threads_list = guild.threads;
-                    let distant_thread = match threads_list.into_iter().filter(|t| t.id == u64_thread).collect::<Vec<GuildChannel>>().first() {
-                        Some(thread) => thread.clone(),
-                        None => {
-                            write_error_log(format!("Thread not found: {}", f.to_thread));
-                            return;
-                        }
-                    };
-
-                    match distant_thread.id.say(&ctx.http, format!("`{}`: {}", message_owner_name, message_without_quotes)).await {
-                        Ok(_) => {
-                            write_info_log(format!("Replicated message: {:?}", msg.content));
-                            // let _ = msg.channel_id.say(&ctx.http, format!("Found thread named: {:?} in guild {}", distant_thread.name, distant_thread.guild_id)).await;
-                            let _ = msg.react(&ctx.http, ROCKET_EMOJI).await;
-                        }
-                        Err(why) => {
-                            write_error_log(format!("Error sending message: {why:?}"));
-                            let _ = msg.react(&ctx.http, BOMB_EXPLODED_EMOJI).await;
-                        }
-                    }
+        let mut copies = Vec::new();
+        for (to_guild, result) in forwarded.copies {
+            match result {
+                Ok(copy) => {
+                    write_info_log(format!("Replicated message: {:?}", msg.content));
+                    let _ = msg.react(&ctx.http, ROCKET_EMOJI).await;
+                    copies.push((to_guild, copy));
+                }
+                Err(err) => {
+                    write_error_log(err.message);
+                    let _ = msg.react(&ctx.http, BOMB_EXPLODED_EMOJI).await;
                 }
             }
-            _ => {
-                write_info_log("No replication pair found".to_string());
-            }
         }
+
+        if forwarded.filtered {
+            let _ = msg.react(&ctx.http, FILTERED_EMOJI).await;
+        }
+
+        self.forward_copies(&ctx, copies).await;
     }
 
-    async fn reaction_add(&self, ctx: Context, add_reaction: Reaction) {
-        write_info_log(format!("Reaction added: {:?}", add_reaction));
+    // Mirror edits of a replicated message onto every copy of it recorded in replication_message_pairs.
+    // A copy whose pair filters now block the message is deleted instead.
+    async fn message_update(&self, ctx: Context, _old: Option<Message>, new: Option<Message>, event: MessageUpdateEvent) {
+        // Embed resolution and other partial updates do not touch the content
+        if event.content.is_none() {
+            return;
+        }
 
-        //ignore if bot
-        match add_reaction.member {
-            Some(user) => {
-                if user.user.bot {
+        // Filters look at the author and their roles, not only at the content
+        let edited = match new {
+            Some(new) => new,
+            None => match event.channel_id.message(&ctx.http, event.id).await {
+                Ok(message) => message,
+                Err(why) => {
+                    write_error_log(format!("Error fetching edited message: {why:?}"));
                     return;
                 }
-            }
-            _ => {}
+            },
+        };
+        let author = edited.author.clone();
+
+        // Edits the bot makes on copies are already covered by the edit of their origin
+        if author.id == ctx.cache.current_user().id {
+            return;
         }
-        // add_reaction.channel_id.say(&ctx.http, format!("Reaction added: {:?}", add_reaction)).await;
 
-        let _db_access = match self.pool.pool.get() {
-            Ok(conn) => crate::database::DBAccessManager::new(conn),
+        let _db_access = match self.get_access() {
+            Ok(access) => access,
             Err(err) => {
-                write_error_log(format!("Error getting connection from pool: {}", err.to_string()));
+                write_error_log(err.message);
                 return;
             }
         };
 
-        let guild_id = add_reaction.guild_id.unwrap_or_default().get() as i64;
-        let channel_id = add_reaction.channel_id.get() as i64;
-        let message_id = add_reaction.message_id.get() as i64;
-        let user_id = add_reaction.user_id.unwrap_or_default().get() as i64;
-
-        match _db_access.get_replication_reply_full(guild_id, channel_id, message_id) {
-            Ok(replication_reply_data) => {
-                write_info_log(format!("Replication reply found: {:?}", replication_reply_data));
-
-                if replication_reply_data.message_owner == user_id {
-                    write_info_log("Message owner".to_string());
-                    let _ = add_reaction.channel_id.say(&ctx.http, "Message owner").await;
-                    let _ = add_reaction.channel_id.delete_message(&ctx.http, add_reaction.message_id).await;
-
-                    if add_reaction.emoji.unicode_eq(format!("{UP_EMOJI}").as_str()) {
-                        write_info_log("UP_EMOJI".to_string());
-                        // let _ = add_reaction.channel_id.say(&ctx.http, format!("UP_EMOJI -> {}", add_reaction.emoji)).await;
-
-                        let _db_access = match self.pool.pool.get() {
-                            Ok(conn) => crate::database::DBAccessManager::new(conn),
-                            Err(err) => {
-                                write_error_log(format!("Error getting connection from pool: {}", err.to_string()));
-                                return;
-                            }
-                        };
-
-                        let _ = _db_access.update_replication_reply_status(guild_id, channel_id, true, "active".to_string());
-
-                        let current_thread_name = add_reaction.channel_id.name(&ctx.http).await.unwrap_or("Replicated thread".to_string());
-
-                        let parent_forum = _db_access.get_parent_forum_from_message_id(guild_id, add_reaction.message_id.into()).unwrap_or_default();
-
-                        let _remote_guilds = match _db_access.get_replication_forum_pair(guild_id, parent_forum) {
-                            Ok(_remote_pairs) => {
-                                add_reaction.channel_id.say(&ctx.http, format!("Remote pairs: {:?}", _remote_pairs)).await;
-
-                                for r in _remote_pairs {
-                                    let guild: Result<Guild, String> = match ctx.cache.guild(r.to_guild as u64) {
-                                        Some(guild) => Ok(guild.clone()),
-                                        _ => {
-                                            write_error_log(format!("Guild not found: {}", r.to_guild));
-
-                                            Err("Guild not found".to_string())
-                                        }
-                                    };
-
-                                    let guild = match guild {
-                                        Ok(guild) => guild,
-                                        Err(err) => {
-                                            write_error_log(format!("Error getting guild: {}", err));
-                                            add_reaction.channel_id.say(&ctx.http, format!("Error getting guild: {}", err)).await;
-                                            return;
-                                        }
-                                    };
-
-                                    let u64_forum = r.to_forum as u64;
-                                    let as_nonzerou64_channel = NonZeroU64::new(u64_forum).unwrap();
-                                    let forum: ChannelId = as_nonzerou64_channel.into();
-
-                                    let remote_channel = guild.channels.get(&forum).unwrap().clone();
-
-                                    let init_message = CreateMessage::new().content(format!("FIRST MSG - {} - REPLICATED", current_thread_name));
-
-                                    let forum_post = CreateForumPost::new(format!("{} - REPLICATED", current_thread_name), init_message);
-
-                                    // let new_thread = remote_channel.create_thread(&ctx.http, thread_dup).await;
-                                    let new_thread = remote_channel.create_forum_post(&ctx.http, forum_post).await;
-                                    match new_thread {
-                                        Ok(new_thread_data) => {
-                                            let first = ReplicationThreadPairData {
-                                                from_guild: guild_id,
-                                                from_thread: channel_id,
-                                                to_guild: r.to_guild,
-                                                to_thread: new_thread_data.id.get() as i64,
-                                                replication_reply_id: replication_reply_data.id,
-                                            };
-                                            let second = ReplicationThreadPairData {
-                                                from_guild: r.to_guild,
-                                                from_thread: new_thread_data.id.get() as i64,
-                                                to_guild: guild_id,
-                                                to_thread: channel_id,
-                                                replication_reply_id: replication_reply_data.id,
-                                            };
-                                            let url_one = format!("g: {} id: {} -> https://discord.com/channels/{}/{} ?", first.from_guild, first.from_thread, first.from_guild, first.from_thread);
-                                            let url_two = format!("g: {} id: {} -> https://discord.com/channels/{}/{} ?", second.from_guild, second.from_thread, second.from_guild, second.from_thread);
-
-                                            let _ = _db_access.create_replication_thread_pair(first);
-                                            let _ = _db_access.create_replication_thread_pair(second);
-
-
-                                            let _ = add_reaction.channel_id.say(&ctx.http, format!("Thread created: {} in guild {} FROM {} TO {}", new_thread_data.name, guild.name, url_one, url_two)).await;
-                                        }
-                                        Err(e) => {
-                                            write_error_log(format!("Error creating thread: {}", e));
-                                            let _ = add_reaction.channel_id.say(&ctx.http, format!("Error creating thread: {}", e)).await;
-                                        }
-                                    }
-                                }
-                            }
-                            _ => {}
-                        };
-
-                        // todo!("Add pair channel in other server");
-                        // todo!("Save pair to handle it on message");
-                    } else if add_reaction.emoji.unicode_eq(format!("{DOWN_EMOJI}").as_str()) {
-                        write_info_log("DOWN_EMOJI".to_string());
-                        // let _ = add_reaction.channel_id.say(&ctx.http, format!("DOWN_EMOJI -> {}", add_reaction.emoji)).await;
-                        let _ = _db_access.update_replication_reply_status(guild_id, channel_id, true, "inactive".to_string());
-                    } else {
-                        write_info_log("Not a valid reaction".to_string());
-                        let _ = add_reaction.channel_id.say(&ctx.http, format!("Not a valid reaction -> {}", add_reaction.emoji)).await;
-                    }
+        // Every copy down the chain, not only the direct ones
+        let mut replicated = match _db_access.get_replication_message_copies(event.channel_id.get() as i64, event.id.get() as i64) {
+            Ok(replicated) => replicated,
+            Err(err) => {
+                write_error_log(format!("Error getting replicated messages: {}", err.message));
+                return;
+            }
+        };
 
-                    return;
-                } else {
-                    write_info_log("Not message owner".to_string());
-                    let _ = add_reaction.channel_id.say(&ctx.http, "Not message owner").await;
+        let source_guild = event.guild_id.unwrap_or_default();
+
+        // Each copy replies to the counterpart found in the channel it was forwarded from, so the
+        // chain is walked one hop at a time, as `forward_copies` does when sending
+        replicated.sort_by_key(|r| r.hops);
+        let mut referenced: HashMap<i64, MessageId> = HashMap::new();
+        if let Some(message_id) = edited.message_reference.as_ref().and_then(|r| r.message_id) {
+            referenced.insert(event.channel_id.get() as i64, message_id);
+        }
+
+        for r in replicated {
+            let distant_channel = ChannelId::new(r.to_channel as u64);
+            let distant_message = MessageId::new(r.to_message as u64);
+
+            let pair = _db_access.get_replication_forum_pair_for_threads(r.from_channel, r.to_channel).ok();
+            if let Some(reason) = pair.as_ref().and_then(|pair| self.filtered(&ctx, source_guild.get() as i64, &edited, pair.id)) {
+                match self.delete_copy(&ctx, &r).await {
+                    Ok(_) => write_info_log(format!("Deleted copy {} of edited message {}: {}", distant_message, event.id, reason)),
+                    Err(err) => write_error_log(format!("Error deleting filtered copy {}: {}", distant_message, err.message)),
                 }
+                continue;
             }
-            Err(err) => {
-                write_error_log(format!("Error getting replication reply: {}", err.message));
-                let _ = add_reaction.channel_id.say(&ctx.http, format!("Error getting replication reply: {}", err.message)).await;
+
+            // Same formatting as the copy got when it was sent, attachments that did not fit included
+            let reference = referenced.get(&r.from_channel).map(|m| (ChannelId::new(r.from_channel as u64), *m));
+            let reply_to = self.reply_counterpart(reference, distant_channel, r.webhook_id.is_some()).unwrap_or_default();
+            if let Some(reply_to) = reply_to {
+                referenced.insert(r.to_channel, reply_to);
+            }
+            let body = match self.replica_body(&ctx, &edited, source_guild.get() as i64, r.to_guild, reply_to, r.webhook_id.is_some()) {
+                Ok(body) => body,
+                Err(err) => {
+                    write_error_log(format!("Error rendering edit of {}: {}", distant_message, err.message));
+                    continue;
+                }
+            };
+            let limit = ctx.cache.guild(r.to_guild as u64).map(|g| upload_limit(g.premium_tier)).unwrap_or(upload_limit(Default::default()));
+            let fallback = pair.map(|p| p.attachment_fallback()).unwrap_or(AttachmentFallback::Link);
+            let content = with_fallback_lines(&body, &oversized_lines(&edited.attachments, limit, fallback));
+
+            let result = match r.webhook_id {
+                Some(webhook_id) => match self.webhook_by_id(&ctx, webhook_id).await {
+                    Ok(webhook) => {
+                        let edit = EditWebhookMessage::new().content(content).in_thread(distant_channel).allowed_mentions(no_mentions());
+                        webhook.edit_message(&ctx.http, distant_message, edit).await
+                            .map_err(|why| AppError::new(format!("{why:?}").as_str(), ErrorType::DistantServer))
+                    }
+                    Err(err) => Err(err),
+                },
+                None => {
+                    let edit = EditMessage::new().content(content).allowed_mentions(no_mentions());
+                    distant_channel.edit_message(&ctx.http, distant_message, edit).await
+                        .map_err(|why| AppError::new(format!("{why:?}").as_str(), ErrorType::DistantServer))
+                }
+            };
+
+            match result {
+                Ok(_) => {
+                    write_info_log(format!("Replicated edit of {} to {} in {}", event.id, distant_message, distant_channel));
+                }
+                Err(err) => {
+                    write_error_log(format!("Error editing replicated message {}: {}", distant_message, err.message));
+                }
             }
         }
     }
 
-    async fn reaction_remove(&self, ctx: Context, remove_reaction: Reaction) {
-        write_info_log(format!("Reaction removed: {:?}", remove_reaction));
+    async fn message_delete(&self, ctx: Context, channel_id: ChannelId, deleted_message_id: MessageId, _guild_id: Option<GuildId>) {
+        self.replicate_deletion(&ctx, channel_id, deleted_message_id).await;
+    }
+
+    async fn message_delete_bulk(&self, ctx: Context, channel_id: ChannelId, multiple_deleted_messages_ids: Vec<MessageId>, _guild_id: Option<GuildId>) {
+        for message_id in multiple_deleted_messages_ids {
+            self.replicate_deletion(&ctx, channel_id, message_id).await;
+        }
+    }
+
+    async fn reaction_add(&self, ctx: Context, add_reaction: Reaction) {
+        write_info_log(format!("Reaction added: {:?}", add_reaction));
+
+        if add_reaction.user_id == Some(ctx.cache.current_user().id) {
+            return;
+        }
+
         //ignore if bot
-        match remove_reaction.member {
+        match &add_reaction.member {
             Some(user) => {
                 if user.user.bot {
                     return;
@@ -289,44 +347,43 @@ impl EventHandler for crate::handler::Handler {
             }
             _ => {}
         }
-        // add_reaction.channel_id.say(&ctx.http, format!("Reaction added: {:?}", add_reaction)).await;
-
-        let _db_access = match self.pool.pool.get() {
-            Ok(conn) => crate::database::DBAccessManager::new(conn),
-            Err(err) => {
-                write_error_log(format!("Error getting connection from pool: {}", err.to_string()));
-                return;
-            }
-        };
 
-        let guild_id = remove_reaction.guild_id.unwrap_or_default().get() as i64;
-        let channel_id = remove_reaction.channel_id.get() as i64;
-        let message_id = remove_reaction.message_id.get() as i64;
-        let user_id = remove_reaction.user_id.unwrap_or_default().get() as i64;
+        self.mirror_reaction(&ctx, &add_reaction, true).await;
+    }
 
-        match _db_access.get_replication_reply_full(guild_id, channel_id, message_id) {
-            Ok(replication_reply_data) => {
-                write_info_log(format!("Replication reply found: {:?}", replication_reply_data));
+    async fn reaction_remove(&self, ctx: Context, remove_reaction: Reaction) {
+        write_info_log(format!("Reaction removed: {:?}", remove_reaction));
 
-                if replication_reply_data.message_owner == user_id {
-                    write_info_log("Message owner".to_string());
-                    let _ = _db_access.delete_replication_reply(replication_reply_data.id);
-                    // delete message
-                    let _ = remove_reaction.channel_id.delete_message(&ctx.http, remove_reaction.message_id).await;
+        if remove_reaction.user_id == Some(ctx.cache.current_user().id) {
+            return;
+        }
+        //ignore if bot
+        match &remove_reaction.member {
+            Some(user) => {
+                if user.user.bot {
+                    return;
                 }
             }
             _ => {}
         }
+
+        self.mirror_reaction(&ctx, &remove_reaction, false).await;
     }
 
     async fn reaction_remove_all(&self, ctx: Context, channel_id: ChannelId, message_id: MessageId) {
         write_info_log(format!("All reactions removed from message: {} in channel: {}", message_id, channel_id));
     }
 
+    async fn thread_update(&self, ctx: Context, _old: Option<GuildChannel>, new: GuildChannel) {
+        write_info_log(format!("Thread updated: {}", new.name));
+
+        self.sync_thread_update(&ctx, &new).await;
+    }
+
     async fn thread_delete(&self, ctx: Context, thread: PartialGuildChannel, channel: Option<GuildChannel>) {
-        // drop replication
-        // Notify ?
         write_info_log(format!("Thread deleted: {}", channel.unwrap_or_default().name));
+
+        self.sync_thread_delete(&ctx, &thread).await;
     }
 
     // Set a handler to be called on the `ready` event. This is called when a shard is booted, and
@@ -339,6 +396,42 @@ impl EventHandler for crate::handler::Handler {
 
         let guilds = context.cache.guilds().len();
         write_info_log(format!("Guilds in the Cache: {}", guilds));
+
+        for guild in ready.guilds.iter() {
+            self.register_slash_commands(&context, guild.id).await;
+        }
+    }
+
+    // Background tasks replicate into other guilds, so they wait for every guild to be cached.
+    async fn cache_ready(&self, context: Context, _: Vec<GuildId>) {
+        write_info_log(format!("{} unknown members", context.cache.unknown_members()));
+
+        if !self.scheduler_started.swap(true, Ordering::SeqCst) {
+            self.resume_backfills(&context);
+
+            let handler = self.clone();
+            tokio::spawn(async move {
+                handler.run_prompt_scheduler(context).await;
+            });
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        match interaction {
+            Interaction::Command(command) if command.data.name == "pair" => {
+                self.handle_pair_command(&ctx, &command).await;
+            }
+            Interaction::Autocomplete(command) if command.data.name == "pair" => {
+                self.handle_pair_autocomplete(&ctx, &command).await;
+            }
+            Interaction::Component(component) if is_prompt_component(&component.data.custom_id) => {
+                self.handle_prompt_component(&ctx, &component).await;
+            }
+            Interaction::Component(component) if is_moderation_component(&component.data.custom_id) => {
+                self.handle_moderation_component(&ctx, &component).await;
+            }
+            _ => {}
+        }
     }
 
     async fn thread_create(&self, ctx: Context, thread: GuildChannel) {
@@ -355,89 +448,112 @@ impl EventHandler for crate::handler::Handler {
         let guild_id = thread.guild_id.get() as i64;
         let parent_id = thread.parent_id.unwrap_or_default().get() as i64;
 
-        let _check_pair = _db_access.get_replication_forum_pair(guild_id, parent_id);
-        match _check_pair {
-            Ok(data) => {
-                let parsed: Vec<(i64, i64, i64)> = data.iter().map(|i| {
-                    (i.id, i.to_guild, i.to_forum)
-                }).collect();
-
-                for (replication_id, to_guild, to_channel) in parsed {
-                    let _check = _db_access.get_replication_reply(guild_id, thread.id.get() as i64);
-                    if _check.is_ok() {
-                        break;
-                    }
+        let data = match _db_access.get_replication_forum_pair(guild_id, parent_id) {
+            Ok(data) => data,
+            Err(err) => {
+                write_error_log(format!("Error getting replication pair: {}", err.message));
+                let _ = thread.id.say(&ctx.http, format!("Error getting replication pair: {}", err.message)).await;
+                return;
+            }
+        };
+        if data.is_empty() || _db_access.get_replication_reply(guild_id, thread.id.get() as i64).is_ok() {
+            return;
+        }
+
+        let owner = match thread.owner_id {
+            Some(owner) => owner,
+            None => {
+                write_info_log("No thread owner".to_string());
+                let _ = thread.id.say(&ctx.http, "No thread owner").await;
+                return;
+            }
+        };
+        write_info_log(format!("Thread owner: {}", owner));
 
-                    match thread.owner_id {
-                        Some(owner) => {
-                            write_info_log(format!("Thread owner: {}", owner));
-                            let _dto = ReplicationReplyData {
-                                responded: false,
-                                status: "inactive".to_string(),
-                                guild_id: thread.guild_id.get() as i64,
-                                channel_id: thread.id.get() as i64,
-                                replication_pairs: replication_id,
-                                message_id: None,
-                                message_owner: owner.get() as i64,
-                            };
-
-                            let _check = _db_access.get_replication_reply(_dto.guild_id, _dto.channel_id);
-                            if _check.is_ok() {
-                                break;
-                            }
-
-                            match _db_access.create_replication_reply(_dto) {
-                                Ok(d) => {
-                                    write_info_log("Replication reply created".to_string());
-                                }
-                                Err(err) => {
-                                    write_error_log(format!("Error creating replication reply: {}", err.message));
-                                    let _ = thread.id.say(&ctx.http, format!("Error creating replication reply: {}", err.message)).await;
-                                }
-                            };
-
-                            match thread.id.say(&ctx.http, format!("Do you want to pair with https://discord.com/channels/{}/{} ?", to_guild, to_channel)).await {
-                                Ok(_msg_tmp) => {
-                                    let _ = _db_access.update_replication_reply_message_id(thread.guild_id.get() as i64, _msg_tmp.channel_id.get() as i64, Some(_msg_tmp.id.get() as i64));
-
-                                    match _msg_tmp.react(&ctx.http, UP_EMOJI).await {
-                                        Ok(_) => {
-                                            write_info_log(format!("Reacted with {UP_EMOJI}"));
-                                        }
-                                        Err(why) => {
-                                            write_error_log(format!("Error sending message: {why:?}"));
-                                            let _ = thread.id.say(&ctx.http, format!("Error sending message: {why:?}")).await;
-                                        }
-                                    }
-                                    match _msg_tmp.react(&ctx.http, DOWN_EMOJI).await {
-                                        Ok(_) => {
-                                            write_info_log(format!("Reacted with {DOWN_EMOJI}"));
-                                        }
-                                        Err(why) => {
-                                            write_error_log(format!("Error sending message: {why:?}"));
-                                            let _ = thread.id.say(&ctx.http, format!("Error sending message: {why:?}")).await;
-                                        }
-                                    };
-
-                                    write_info_log(format!("Do you want to pair with https://discord.com/channels/{}/{} ?", to_guild, to_channel));
-                                }
-                                Err(why) => {
-                                    write_error_log(format!("Error sending message: {why:?}"));
-                                    let _ = thread.id.say(&ctx.http, format!("Error sending message: {why:?}")).await;
-                                }
-                            };
-                        }
-                        None => {
-                            write_info_log("No thread owner".to_string());
-                            let _ = thread.id.say(&ctx.http, "No thread owner").await;
-                        }
+        // Posts created by a replication must not be offered back, auto_accept pairs would loop
+        if owner == ctx.cache.current_user().id {
+            return;
+        }
+
+        // One reply per target, so the owner can accept some of the paired forums only
+        let mut targets = Vec::new();
+        let mut accepted = Vec::new();
+        let mut queued = Vec::new();
+        for pair in data {
+            let _dto = ReplicationReplyData {
+                status: ReplicationStatus::Pending.as_str().to_string(),
+                guild_id,
+                channel_id: thread.id.get() as i64,
+                replication_pairs: pair.id,
+                message_id: None,
+                message_owner: owner.get() as i64,
+            };
+
+            let reply = match _db_access.create_replication_reply(_dto) {
+                Ok(reply) => reply,
+                Err(err) => {
+                    write_error_log(format!("Error creating replication reply: {}", err.message));
+                    let _ = thread.id.say(&ctx.http, format!("Error creating replication reply: {}", err.message)).await;
+                    continue;
+                }
+            };
+
+            match pair.approval_policy() {
+                ApprovalPolicy::AutoAccept => accepted.push((reply, pair)),
+                ApprovalPolicy::AutoDecline => {
+                    if let Err(err) = _db_access.transition_replication_reply(reply.id, ReplicationStatus::Declined, None, Some("auto_decline".to_string())) {
+                        write_error_log(format!("Error updating replication reply: {}", err.message));
                     }
                 }
+                ApprovalPolicy::TargetModerators => queued.push((reply, pair)),
+                ApprovalPolicy::AskOwner | ApprovalPolicy::ModeratorOnly => targets.push((reply, pair)),
             }
-            Err(err) => {
-                write_error_log(format!("Error getting replication pair: {}", err.message));
-                let _ = thread.id.say(&ctx.http, format!("Error getting replication pair: {}", err.message)).await;
+        }
+
+        // Left pending when the target has no moderation channel, the prompt expiry resolves them
+        for (reply, pair) in queued.iter() {
+            match self.enqueue_replication(&ctx, &thread, reply, pair).await {
+                Ok(_) => {
+                    let _ = thread.id.say(&ctx.http, format!("Waiting for the moderators of {} to approve the replication", guild_name(&ctx.cache, pair.to_guild))).await;
+                }
+                Err(err) => write_error_log(format!("Error queuing replication request {}: {}", reply.id, err.message)),
             }
         }
+
+        if !accepted.is_empty() {
+            let selected: Vec<i64> = accepted.iter().map(|(reply, _)| reply.id).collect();
+            let answer = PromptAnswer {
+                guild_id,
+                thread_id: thread.id,
+                targets: &accepted,
+                selected: &selected,
+                otherwise: ReplicationStatus::Declined,
+                actor_id: None,
+            };
+            let outcome = self.answer_targets(&ctx, &answer).await;
+            write_info_log(format!("Replication of thread {} auto-accepted: {}", thread.id, outcome));
+            let _ = thread.id.say(&ctx.http, outcome).await;
+        }
+        if targets.is_empty() {
+            return;
+        }
+
+        let prompt = CreateMessage::new()
+            .content(prompt_content(&targets))
+            .components(prompt_components(&ctx.cache, &targets));
+
+        match thread.id.send_message(&ctx.http, prompt).await {
+            Ok(_msg_tmp) => {
+                // Only the prompted requests are answered through this message
+                let prompted: Vec<i64> = targets.iter().map(|(reply, _)| reply.id).collect();
+                if let Err(err) = _db_access.update_replication_reply_message_id(&prompted, Some(_msg_tmp.id.get() as i64)) {
+                    write_error_log(format!("Error updating replication reply: {}", err.message));
+                }
+                write_info_log(format!("Replication prompt sent in thread {}", thread.id));
+            }
+            Err(why) => {
+                write_error_log(format!("Error sending message: {why:?}"));
+            }
+        };
     }
 }
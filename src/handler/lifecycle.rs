@@ -0,0 +1,162 @@
+use serenity::all::{ChannelId, Context, CreateMessage, EditThread, GuildChannel, PartialGuildChannel};
+use crate::handler::content::no_mentions;
+use crate::handler::db_access::{ReplicationForumPair, ReplicationStatus, ReplicationThreadPair, ThreadSync};
+use crate::handler::replication::{origin_prefix, original_title, replicated_title};
+use crate::handler::Handler;
+use crate::log::{write_error_log, write_info_log};
+
+impl Handler {
+    // Whether `thread_id` is the thread the replication request was made for, as opposed to a copy.
+    fn is_source_thread(&self, f: &ReplicationThreadPair) -> bool {
+        self.get_access()
+            .and_then(|db| db.get_replication_reply_by_id(f.replication_reply_id))
+            .is_ok_and(|reply| reply.channel_id == f.from_thread)
+    }
+
+    fn pair_of(&self, f: &ReplicationThreadPair) -> Option<ReplicationForumPair> {
+        self.get_access()
+            .and_then(|db| db.get_replication_forum_pair_for_threads(f.from_thread, f.to_thread))
+            .ok()
+    }
+
+    // The pair's `thread_sync` going from `f.from_thread` to `f.to_thread`. A copy never rewrites nor
+    // deletes the source thread of another guild, `mirror` is only annotated in that direction.
+    fn thread_sync_of(&self, f: &ReplicationThreadPair, is_source: bool) -> ThreadSync {
+        match self.pair_of(f).map(|pair| pair.thread_sync()).unwrap_or(ThreadSync::Annotate) {
+            ThreadSync::Mirror if !is_source => ThreadSync::Annotate,
+            sync => sync,
+        }
+    }
+
+    /// Carries a rename, archive or lock of a replicated thread over to its counterparts, per the
+    /// pair's `thread_sync` (see `thread_sync_of` for changes made on a copy), paused replications
+    /// included. Only what differs from the counterpart is applied, so the update the bot makes on
+    /// the counterpart does not bounce back.
+    pub(crate) async fn sync_thread_update(&self, ctx: &Context, thread: &GuildChannel) {
+        let found = match self.get_access().and_then(|db| db.get_live_replication_thread_pairs(thread.guild_id.get() as i64, thread.id.get() as i64)) {
+            Ok(found) => found,
+            Err(_) => return,
+        };
+
+        let (archived, locked) = thread.thread_metadata.map_or((false, false), |m| (m.archived, m.locked));
+
+        for f in found {
+            let is_source = self.is_source_thread(&f);
+            let sync = self.thread_sync_of(&f, is_source);
+            if sync == ThreadSync::Off {
+                continue;
+            }
+
+            let counterpart = match ChannelId::new(f.to_thread as u64).to_channel(&ctx.http).await.ok().and_then(|c| c.guild()) {
+                Some(counterpart) => counterpart,
+                None => {
+                    write_error_log(format!("Thread not found: {}", f.to_thread));
+                    continue;
+                }
+            };
+            let (counterpart_archived, counterpart_locked) = counterpart.thread_metadata.map_or((false, false), |m| (m.archived, m.locked));
+
+            let prefix = self.pair_of(&f).and_then(|pair| origin_prefix(&ctx.cache, &pair));
+            let name = if is_source {
+                replicated_title(&thread.name, prefix.as_deref())
+            } else {
+                original_title(&thread.name, prefix.as_deref())
+            };
+
+            let mut changes = Vec::new();
+            let mut edit = EditThread::new();
+            if name != counterpart.name {
+                changes.push(format!("renamed to **{}**", thread.name));
+                edit = edit.name(&name);
+            }
+            if locked != counterpart_locked {
+                changes.push(if locked { "locked" } else { "unlocked" }.to_string());
+                edit = edit.locked(locked);
+            }
+            if archived != counterpart_archived {
+                changes.push(if archived { "archived" } else { "reopened" }.to_string());
+                edit = edit.archived(archived);
+            }
+            if changes.is_empty() {
+                continue;
+            }
+
+            let result = match sync {
+                ThreadSync::Mirror => counterpart.id.edit_thread(&ctx.http, edit).await.map(|_| ()),
+                _ => {
+                    let note = if is_source {
+                        format!("The original thread was {}", changes.join(", "))
+                    } else {
+                        format!("A replicated copy of this thread was {}", changes.join(", "))
+                    };
+                    counterpart.id.send_message(&ctx.http, CreateMessage::new().content(note).allowed_mentions(no_mentions())).await.map(|_| ())
+                }
+            };
+
+            match result {
+                Ok(_) => write_info_log(format!("Thread {} {}: synced to {}", thread.id, changes.join(", "), counterpart.id)),
+                Err(why) => write_error_log(format!("Error syncing thread {} to {}: {why:?}", thread.id, counterpart.id)),
+            }
+        }
+    }
+
+    /// Deletes (or annotates, per `thread_sync`) the copies of a deleted source thread and annotates
+    /// the source of a deleted copy. Revokes the replications and drops the thread pairs so nothing
+    /// is forwarded into a dead thread.
+    pub(crate) async fn sync_thread_delete(&self, ctx: &Context, thread: &PartialGuildChannel) {
+        let found = match self.get_access().and_then(|db| db.get_all_replication_thread_pairs(thread.id.get() as i64)) {
+            Ok(found) => found,
+            Err(err) => {
+                write_error_log(format!("Error getting replicated threads: {}", err.message));
+                return;
+            }
+        };
+
+        // The pair settings are looked up through the thread pairs, before they go away
+        let found: Vec<(ReplicationThreadPair, ThreadSync, bool)> = found.into_iter()
+            .map(|f| {
+                let is_source = self.is_source_thread(&f);
+                let sync = self.thread_sync_of(&f, is_source);
+                (f, sync, is_source)
+            })
+            .collect();
+
+        // Dropped first, the deletion of a mirrored counterpart then finds nothing to sync back
+        match self.get_access().and_then(|db| db.delete_replication_thread_pairs(thread.id.get() as i64)) {
+            Ok(deleted) if deleted > 0 => write_info_log(format!("Thread {} deleted, {} thread pairs removed", thread.id, deleted)),
+            Ok(_) => {}
+            Err(err) => write_error_log(format!("Error removing thread pairs of {}: {}", thread.id, err.message)),
+        }
+
+        for (f, sync, is_source) in found.iter() {
+            let counterpart = ChannelId::new(f.to_thread as u64);
+            let note = if *is_source {
+                "The original thread was deleted, nothing will be replicated anymore"
+            } else {
+                "A replicated copy of this thread was deleted, nothing will be replicated there anymore"
+            };
+            let result = match sync {
+                ThreadSync::Mirror => counterpart.delete(&ctx.http).await.map(|_| ()),
+                ThreadSync::Annotate => counterpart
+                    .send_message(&ctx.http, CreateMessage::new().content(note))
+                    .await
+                    .map(|_| ()),
+                ThreadSync::Off => Ok(()),
+            };
+            if let Err(why) = result {
+                write_error_log(format!("Error syncing deletion of thread {} to {}: {why:?}", thread.id, counterpart));
+            }
+
+            let revoked = self.get_access().and_then(|db| {
+                let reply = db.get_replication_reply_by_id(f.replication_reply_id)?;
+                if reply.current_status().can_transition_to(ReplicationStatus::Revoked) {
+                    db.transition_replication_reply(reply.id, ReplicationStatus::Revoked, None, Some("thread deleted".to_string()))?;
+                }
+                Ok(())
+            });
+            if let Err(err) = revoked {
+                write_error_log(format!("Error revoking replication of thread {}: {}", thread.id, err.message));
+            }
+        }
+    }
+}
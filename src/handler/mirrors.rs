@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use serenity::all::{Cache, ChannelId, ChannelType, Context, CreateChannel, GuildChannel, GuildId};
+use crate::errors::{AppError, ErrorType};
+use crate::handler::db_access::{ReplicationChannelPairData, ReplicationForumPairData, ReplicationMirror, ReplicationMirrorChannelData};
+use crate::handler::slash_commands::{channel_name, guild_name};
+use crate::handler::validation::{is_public, validate_mirrored_pair};
+use crate::handler::Handler;
+use crate::log::{write_error_log, write_info_log};
+
+// Whether `channel` of the source guild is covered by `mirror`, categories included so that the
+// target keeps the same layout. Channels hidden from @everyone are never mirrored.
+fn in_scope(cache: &Cache, mirror: &ReplicationMirror, channel: &GuildChannel) -> bool {
+    if !matches!(channel.kind, ChannelType::Category | ChannelType::Text | ChannelType::News | ChannelType::Forum) {
+        return false;
+    }
+
+    if !cache.guild(channel.guild_id).is_some_and(|guild| is_public(&guild, channel)) {
+        return false;
+    }
+
+    match mirror.from_category {
+        None => true,
+        Some(category) => channel.id.get() as i64 == category || channel.parent_id.is_some_and(|p| p.get() as i64 == category),
+    }
+}
+
+pub fn describe_mirror(cache: &Cache, mirror: &ReplicationMirror) -> String {
+    let scope = match mirror.from_category {
+        Some(category) => format!("{} / {}", guild_name(cache, mirror.from_guild), channel_name(cache, mirror.from_guild, category)),
+        None => guild_name(cache, mirror.from_guild),
+    };
+
+    format!("mirror #{}: {} -> {}", mirror.id, scope, guild_name(cache, mirror.to_guild))
+}
+
+// Announcement channels need a community server, the target gets a plain text channel.
+fn target_kind(kind: ChannelType) -> ChannelType {
+    match kind {
+        ChannelType::News => ChannelType::Text,
+        kind => kind,
+    }
+}
+
+impl Handler {
+    /// Creates the channels of the mirror's scope that are missing in the target and pairs them.
+    /// Categories go first so channels land in their mirrored category. Returns how many channels
+    /// were mirrored; a channel that fails is logged and retried on the next sync.
+    pub(crate) async fn sync_mirror(&self, ctx: &Context, mirror: &ReplicationMirror) -> Result<usize, AppError> {
+        let mut sources: Vec<GuildChannel> = match ctx.cache.guild(mirror.from_guild as u64) {
+            Some(guild) => guild.channels.values().filter(|c| in_scope(&ctx.cache, mirror, c)).cloned().collect(),
+            None => return Err(AppError::new(format!("Guild not found: {}", mirror.from_guild).as_str(), ErrorType::NotFound)),
+        };
+        sources.sort_by_key(|c| (c.kind != ChannelType::Category, c.position));
+
+        let mut mapping = self.mirror_mapping(mirror)?;
+        let mut mirrored = 0;
+        for source in sources.iter() {
+            if mapping.contains_key(&(source.id.get() as i64)) {
+                continue;
+            }
+
+            match self.mirror_channel(ctx, mirror, source, &mut mapping).await {
+                Ok(_) => mirrored += 1,
+                Err(err) => write_error_log(format!("Error mirroring channel {} of mirror {}: {}", source.id, mirror.id, err.message)),
+            }
+        }
+
+        Ok(mirrored)
+    }
+
+    /// Mirrors a channel created in a guild that has mirrors, when it is in their scope.
+    pub(crate) async fn mirror_new_channel(&self, ctx: &Context, channel: &GuildChannel) {
+        let mirrors = match self.get_access().and_then(|db| db.get_replication_mirrors_from_guild(channel.guild_id.get() as i64)) {
+            Ok(mirrors) => mirrors,
+            Err(err) => {
+                write_error_log(format!("Error getting mirrors of guild {}: {}", channel.guild_id, err.message));
+                return;
+            }
+        };
+
+        for mirror in mirrors.iter().filter(|m| in_scope(&ctx.cache, m, channel)) {
+            let result = match self.mirror_mapping(mirror) {
+                Ok(mapping) if mapping.contains_key(&(channel.id.get() as i64)) => continue,
+                Ok(mut mapping) => self.mirror_channel(ctx, mirror, channel, &mut mapping).await,
+                Err(err) => Err(err),
+            };
+            if let Err(err) = result {
+                write_error_log(format!("Error mirroring new channel {} of mirror {}: {}", channel.id, mirror.id, err.message));
+            }
+        }
+    }
+
+    // Source channel id to target channel id.
+    fn mirror_mapping(&self, mirror: &ReplicationMirror) -> Result<HashMap<i64, i64>, AppError> {
+        Ok(self.get_access()?
+            .get_replication_mirror_channels(mirror.id)?
+            .into_iter()
+            .map(|c| (c.from_channel, c.to_channel))
+            .collect())
+    }
+
+    // Finds or creates the counterpart of `source` in the target, records it and pairs both channels.
+    async fn mirror_channel(&self, ctx: &Context, mirror: &ReplicationMirror, source: &GuildChannel, mapping: &mut HashMap<i64, i64>) -> Result<(), AppError> {
+        let kind = target_kind(source.kind);
+        let parent = source.parent_id
+            .and_then(|p| mapping.get(&(p.get() as i64)))
+            .map(|p| ChannelId::new(*p as u64));
+
+        // A channel of the same name and kind already in place is reused
+        let existing = ctx.cache.guild(mirror.to_guild as u64).and_then(|g| {
+            g.channels.values()
+                .find(|c| c.kind == kind && c.name == source.name && c.parent_id == parent)
+                .cloned()
+        });
+
+        let target = match existing {
+            Some(target) => target,
+            None => {
+                let mut builder = CreateChannel::new(&source.name).kind(kind).nsfw(source.nsfw);
+                if let Some(topic) = &source.topic {
+                    builder = builder.topic(topic);
+                }
+                if let Some(parent) = parent {
+                    builder = builder.category(parent);
+                }
+
+                GuildId::new(mirror.to_guild as u64).create_channel(&ctx.http, builder).await
+                    .map_err(|why| AppError::new(format!("Error creating channel {}: {why:?}", source.name).as_str(), ErrorType::DistantServer))?
+            }
+        };
+
+        if source.kind != ChannelType::Category {
+            validate_mirrored_pair(ctx, source, &target).await?;
+        }
+
+        let (from_channel, to_channel) = (source.id.get() as i64, target.id.get() as i64);
+        let _db_access = self.get_access()?;
+        _db_access.create_replication_mirror_channel(ReplicationMirrorChannelData {
+            mirror_id: mirror.id,
+            from_channel,
+            to_channel,
+        })?;
+        mapping.insert(from_channel, to_channel);
+
+        match source.kind {
+            ChannelType::Forum => {
+                let paired = _db_access.get_replication_forum_pair(mirror.from_guild, from_channel)
+                    .is_ok_and(|pairs| pairs.iter().any(|p| p.to_forum == to_channel));
+                if !paired {
+                    _db_access.create_replication_forum_pair(ReplicationForumPairData {
+                        from_guild: mirror.from_guild,
+                        from_forum: from_channel,
+                        to_guild: mirror.to_guild,
+                        to_forum: to_channel,
+                    })?;
+                }
+            }
+            ChannelType::Text | ChannelType::News => {
+                let paired = _db_access.get_replication_channel_pairs(mirror.from_guild, from_channel)
+                    .is_ok_and(|pairs| pairs.iter().any(|p| p.to_channel == to_channel));
+                if !paired {
+                    _db_access.create_replication_channel_pair(ReplicationChannelPairData {
+                        from_guild: mirror.from_guild,
+                        from_channel,
+                        to_guild: mirror.to_guild,
+                        to_channel,
+                    })?;
+                }
+            }
+            _ => {}
+        }
+
+        write_info_log(format!("Mirror {}: channel {} mirrored to {} in guild {}", mirror.id, from_channel, to_channel, mirror.to_guild));
+        Ok(())
+    }
+}
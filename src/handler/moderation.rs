@@ -0,0 +1,169 @@
+use serenity::all::{
+    ButtonStyle, Cache, ChannelId, ComponentInteraction, Context, CreateActionRow, CreateButton, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, EditInteractionResponse, EditMessage, GuildChannel, MessageId,
+};
+use crate::errors::{AppError, ErrorType};
+use crate::handler::content::no_mentions;
+use crate::handler::db_access::{ReplicationForumPair, ReplicationQueueEntry, ReplicationQueueEntryData, ReplicationReply, ReplicationStatus};
+use crate::handler::prompts::PromptAnswer;
+use crate::handler::slash_commands::{channel_name, guild_name};
+use crate::handler::Handler;
+use crate::log::{write_error_log, write_info_log};
+
+const MODERATION_PREFIX: &str = "moderation";
+
+// Custom ids look like `moderation:<action>:<queue entry id>`.
+fn custom_id(action: &str, queue_id: i64) -> String {
+    format!("{}:{}:{}", MODERATION_PREFIX, action, queue_id)
+}
+
+fn parse_custom_id(custom_id: &str) -> Option<(&str, i64)> {
+    let mut parts = custom_id.split(':');
+    match (parts.next(), parts.next(), parts.next().and_then(|id| id.parse::<i64>().ok())) {
+        (Some(MODERATION_PREFIX), Some(action), Some(queue_id)) => Some((action, queue_id)),
+        _ => None,
+    }
+}
+
+pub fn is_moderation_component(custom_id: &str) -> bool {
+    parse_custom_id(custom_id).is_some()
+}
+
+/// One line describing a queued request, as shown in the moderation channel and by `queue`.
+pub fn queue_description(cache: &Cache, queue_id: i64, reply: &ReplicationReply, pair: &ReplicationForumPair) -> String {
+    format!(
+        "#{}: https://discord.com/channels/{}/{} by <@{}> from {} / {} -> {}",
+        queue_id,
+        reply.guild_id,
+        reply.channel_id,
+        reply.message_owner,
+        guild_name(cache, pair.from_guild),
+        channel_name(cache, pair.from_guild, pair.from_forum),
+        channel_name(cache, pair.to_guild, pair.to_forum),
+    )
+}
+
+fn queue_components(queue_id: i64) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(custom_id("accept", queue_id)).label("Approve").style(ButtonStyle::Success),
+        CreateButton::new(custom_id("decline", queue_id)).label("Reject").style(ButtonStyle::Danger),
+    ])]
+}
+
+impl Handler {
+    /// Posts a pending replication request into the moderation channel of the pair's target guild,
+    /// where its moderators approve or reject it before the forum post is created.
+    pub(crate) async fn enqueue_replication(&self, ctx: &Context, thread: &GuildChannel, reply: &ReplicationReply, pair: &ReplicationForumPair) -> Result<ReplicationQueueEntry, AppError> {
+        let moderation_channel = self.get_access()?
+            .get_replication_moderation_channel(pair.to_guild)?
+            .ok_or_else(|| AppError::new(
+                format!("No moderation channel configured in {}", guild_name(&ctx.cache, pair.to_guild)).as_str(),
+                ErrorType::NotFound,
+            ))?;
+
+        let entry = self.get_access()?.create_replication_queue_entry(ReplicationQueueEntryData {
+            replication_reply_id: reply.id,
+            guild_id: pair.to_guild,
+            channel_id: moderation_channel.channel_id,
+            message_id: None,
+        })?;
+
+        let content = format!("New post **{}** waiting for approval\n{}", thread.name, queue_description(&ctx.cache, entry.id, reply, pair));
+        let message = CreateMessage::new()
+            .content(content)
+            .allowed_mentions(no_mentions())
+            .components(queue_components(entry.id));
+
+        let sent = ChannelId::new(moderation_channel.channel_id as u64)
+            .send_message(&ctx.http, message)
+            .await
+            .map_err(|why| AppError::new(format!("Error posting in the moderation channel: {why}").as_str(), ErrorType::DistantServer))?;
+
+        write_info_log(format!("Replication request {} queued in guild {}", reply.id, pair.to_guild));
+
+        self.get_access()?.update_replication_queue_message_id(entry.id, sent.id.get() as i64)
+    }
+
+    /// Approves or rejects a queued request: replicates the thread when approved, records the answer,
+    /// then closes the queue message and tells the source thread. Returns what happened.
+    pub(crate) async fn process_queue_entry(&self, ctx: &Context, queue_id: i64, accept: bool, actor_id: Option<i64>) -> Result<String, AppError> {
+        let (entry, (reply, pair)) = self.get_access()?.get_replication_queue_entry(queue_id)?;
+
+        if reply.current_status() != ReplicationStatus::Pending {
+            self.close_queue_entry(ctx, &entry, format!("Replication request #{} was already answered", entry.id)).await;
+            return Err(AppError::new(format!("Replication request #{} was already answered", entry.id).as_str(), ErrorType::Validation));
+        }
+
+        let thread_id = ChannelId::new(reply.channel_id as u64);
+        let selected = if accept { vec![reply.id] } else { Vec::new() };
+        let targets = [(reply, pair)];
+        let answer = PromptAnswer {
+            guild_id: targets[0].0.guild_id,
+            thread_id,
+            targets: &targets,
+            selected: &selected,
+            otherwise: ReplicationStatus::Declined,
+            actor_id,
+        };
+        let outcome = self.answer_targets(ctx, &answer).await;
+
+        let answered_by = actor_id.map_or("".to_string(), |actor| format!(" by <@{}>", actor));
+        let verdict = if accept { "Approved" } else { "Rejected" };
+        self.close_queue_entry(ctx, &entry, format!("{}{}: {}\n{}", verdict, answered_by, queue_description(&ctx.cache, entry.id, &targets[0].0, &targets[0].1), outcome)).await;
+
+        let notice = format!("Moderators of {} answered: {}", guild_name(&ctx.cache, targets[0].1.to_guild), outcome);
+        if let Err(why) = thread_id.send_message(&ctx.http, CreateMessage::new().content(notice).allowed_mentions(no_mentions())).await {
+            write_error_log(format!("Error notifying thread {}: {why:?}", thread_id));
+        }
+
+        Ok(outcome)
+    }
+
+    // Replaces the queue message with its final state and drops the entry from the queue.
+    pub(crate) async fn close_queue_entry(&self, ctx: &Context, entry: &ReplicationQueueEntry, content: String) {
+        if let Some(message_id) = entry.message_id {
+            let edit = EditMessage::new().content(content).allowed_mentions(no_mentions()).components(Vec::new());
+            if let Err(why) = ChannelId::new(entry.channel_id as u64).edit_message(&ctx.http, MessageId::new(message_id as u64), edit).await {
+                write_error_log(format!("Error editing queue message: {why:?}"));
+            }
+        }
+
+        if let Err(err) = self.get_access().and_then(|db| db.delete_replication_queue_entry(entry.id)) {
+            write_error_log(format!("Error removing replication request from the queue: {}", err.message));
+        }
+    }
+
+    /// Answers Approve/Reject clicks in a moderation channel, for members who can manage threads.
+    pub(crate) async fn handle_moderation_component(&self, ctx: &Context, component: &ComponentInteraction) {
+        let (action, queue_id) = match parse_custom_id(&component.data.custom_id) {
+            Some(parsed) => parsed,
+            None => return,
+        };
+
+        let is_moderator = component.member.as_ref()
+            .and_then(|member| member.permissions)
+            .is_some_and(|permissions| permissions.manage_threads());
+        if !is_moderator {
+            let response = CreateInteractionResponseMessage::new().content("Only moderators can answer replication requests").ephemeral(true);
+            if let Err(why) = component.create_response(&ctx.http, CreateInteractionResponse::Message(response)).await {
+                write_error_log(format!("Error answering moderation interaction: {why:?}"));
+            }
+            return;
+        }
+
+        // Creating the remote post can take longer than the interaction response window
+        if let Err(why) = component.defer_ephemeral(&ctx.http).await {
+            write_error_log(format!("Error deferring moderation interaction: {why:?}"));
+            return;
+        }
+
+        let content = match self.process_queue_entry(ctx, queue_id, action == "accept", Some(component.user.id.get() as i64)).await {
+            Ok(outcome) => outcome,
+            Err(err) => err.message,
+        };
+
+        if let Err(why) = component.edit_response(&ctx.http, EditInteractionResponse::new().content(content)).await {
+            write_error_log(format!("Error answering moderation interaction: {why:?}"));
+        }
+    }
+}
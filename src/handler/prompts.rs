@@ -0,0 +1,251 @@
+use serenity::all::{
+    ButtonStyle, Cache, ChannelId, ComponentInteraction, ComponentInteractionDataKind, Context, CreateActionRow, CreateButton,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption,
+    EditInteractionResponse, EditMessage, MessageId,
+};
+use crate::handler::db_access::{ApprovalPolicy, ReplicationBackfillJobData, ReplicationForumPair, ReplicationReply, ReplicationStatus};
+use crate::handler::slash_commands::{channel_name, guild_name};
+use crate::handler::Handler;
+use crate::log::{write_error_log, write_info_log};
+
+const PROMPT_PREFIX: &str = "replication";
+
+// Discord refuses select menus with more options. Targets past it are only answered by the buttons.
+const MAX_SELECT_OPTIONS: usize = 25;
+
+// Custom ids look like `replication:<action>:<replication reply id>`.
+fn custom_id(action: &str, reply_id: i64) -> String {
+    format!("{}:{}:{}", PROMPT_PREFIX, action, reply_id)
+}
+
+fn parse_custom_id(custom_id: &str) -> Option<(&str, i64)> {
+    let mut parts = custom_id.split(':');
+    match (parts.next(), parts.next(), parts.next().and_then(|id| id.parse::<i64>().ok())) {
+        (Some(PROMPT_PREFIX), Some(action), Some(reply_id)) => Some((action, reply_id)),
+        _ => None,
+    }
+}
+
+/// An answer to the replication request of a thread, from a member or from the scheduler.
+pub(crate) struct PromptAnswer<'a> {
+    pub guild_id: i64,
+    pub thread_id: ChannelId,
+    pub targets: &'a [(ReplicationReply, ReplicationForumPair)],
+    /// Reply ids of the targets to replicate to.
+    pub selected: &'a [i64],
+    /// Status given to the targets that are not selected.
+    pub otherwise: ReplicationStatus,
+    /// Who answered, `None` when the scheduler does.
+    pub actor_id: Option<i64>,
+}
+
+pub fn is_prompt_component(custom_id: &str) -> bool {
+    parse_custom_id(custom_id).is_some()
+}
+
+fn target_label(cache: &Cache, pair: &ReplicationForumPair) -> String {
+    format!("{} / {}", guild_name(cache, pair.to_guild), channel_name(cache, pair.to_guild, pair.to_forum))
+}
+
+// Only the thread owner answers for `ask_owner` pairs, members who can manage threads for
+// `moderator_only` pairs.
+fn can_answer(pair: &ReplicationForumPair, is_owner: bool, is_moderator: bool) -> bool {
+    match pair.approval_policy() {
+        ApprovalPolicy::AskOwner => is_owner,
+        ApprovalPolicy::ModeratorOnly => is_moderator,
+        ApprovalPolicy::AutoAccept | ApprovalPolicy::AutoDecline | ApprovalPolicy::TargetModerators => false,
+    }
+}
+
+pub fn prompt_content(targets: &[(ReplicationReply, ReplicationForumPair)]) -> String {
+    let mut content = match targets {
+        [(_, pair)] => format!("Do you want to pair with https://discord.com/channels/{}/{} ?", pair.to_guild, pair.to_forum),
+        _ => "Do you want to replicate this post? Approve to replicate everywhere, or choose the servers below.".to_string(),
+    };
+
+    if targets.iter().any(|(_, pair)| pair.approval_policy() == ApprovalPolicy::ModeratorOnly) {
+        content.push_str("\nSome targets can only be approved by a moderator.");
+    }
+
+    content
+}
+
+/// Approve/Decline buttons, plus a select menu of the targets when the forum has several pairs.
+/// The prompt is keyed on the first target's reply, each option on the reply of its own target.
+pub fn prompt_components(cache: &Cache, targets: &[(ReplicationReply, ReplicationForumPair)]) -> Vec<CreateActionRow> {
+    let prompt_id = match targets.first() {
+        Some((reply, _)) => reply.id,
+        None => return Vec::new(),
+    };
+
+    let mut rows = vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(custom_id("accept", prompt_id)).label("Approve").style(ButtonStyle::Success),
+        CreateButton::new(custom_id("decline", prompt_id)).label("Decline").style(ButtonStyle::Danger),
+    ])];
+
+    if targets.len() > 1 {
+        let options = targets.iter()
+            .take(MAX_SELECT_OPTIONS)
+            .map(|(reply, pair)| CreateSelectMenuOption::new(target_label(cache, pair), reply.id.to_string()))
+            .collect::<Vec<CreateSelectMenuOption>>();
+
+        rows.push(CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(custom_id("select", prompt_id), CreateSelectMenuKind::String { options })
+                .placeholder("Choose where to replicate")
+                .min_values(1)
+                .max_values(targets.len().min(MAX_SELECT_OPTIONS) as u8),
+        ));
+    }
+
+    rows
+}
+
+impl Handler {
+    /// Answers a click on a pairing prompt for the targets the user may answer (see `can_answer`).
+    /// Every reply is ephemeral and the prompt itself is edited to show the outcome once it is answered.
+    ///
+    /// Each target has its own `replications_reply` row: the chosen ones become `accepted`, the others
+    /// `declined`, so a post can be replicated to some of the paired forums only.
+    pub(crate) async fn handle_prompt_component(&self, ctx: &Context, component: &ComponentInteraction) {
+        let (action, reply_id) = match parse_custom_id(&component.data.custom_id) {
+            Some(parsed) => parsed,
+            None => return,
+        };
+
+        let reply = match self.get_access().and_then(|db| db.get_replication_reply_by_id(reply_id)) {
+            Ok(reply) => reply,
+            Err(err) => {
+                write_error_log(format!("Error getting replication reply: {}", err.message));
+                self.prompt_feedback(ctx, component, "This replication request no longer exists").await;
+                return;
+            }
+        };
+
+        let pending: Vec<(ReplicationReply, ReplicationForumPair)> = self.get_access()
+            .and_then(|db| db.get_replication_reply_targets(reply.guild_id, reply.channel_id))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(target, _)| target.current_status() == ReplicationStatus::Pending)
+            .collect();
+        if pending.is_empty() {
+            self.prompt_feedback(ctx, component, "This replication request was already answered").await;
+            return;
+        }
+
+        let is_owner = reply.message_owner == component.user.id.get() as i64;
+        let is_moderator = component.member.as_ref()
+            .and_then(|member| member.permissions)
+            .is_some_and(|permissions| permissions.manage_threads());
+        let (answerable, remaining): (Vec<_>, Vec<_>) = pending.into_iter()
+            .partition(|(_, pair)| can_answer(pair, is_owner, is_moderator));
+        if answerable.is_empty() {
+            self.prompt_feedback(ctx, component, "You cannot answer this replication request").await;
+            return;
+        }
+
+        // Creating the remote posts can take longer than the interaction response window
+        if let Err(why) = component.defer_ephemeral(&ctx.http).await {
+            write_error_log(format!("Error deferring prompt interaction: {why:?}"));
+            return;
+        }
+
+        let selected: Vec<i64> = answerable.iter()
+            .filter(|(target, _)| match (action, &component.data.kind) {
+                ("accept", _) => true,
+                ("select", ComponentInteractionDataKind::StringSelect { values }) => values.contains(&target.id.to_string()),
+                _ => false,
+            })
+            .map(|(target, _)| target.id)
+            .collect();
+
+        let actor_id = Some(component.user.id.get() as i64);
+        let answer = PromptAnswer {
+            guild_id: reply.guild_id,
+            thread_id: component.channel_id,
+            targets: &answerable,
+            selected: &selected,
+            otherwise: ReplicationStatus::Declined,
+            actor_id,
+        };
+        let outcome = self.answer_targets(ctx, &answer).await;
+        write_info_log(format!("Replication request of thread {} answered: {}", reply.channel_id, outcome));
+
+        if remaining.is_empty() {
+            self.close_prompt(ctx, component.channel_id, component.message.id, outcome.clone()).await;
+        } else {
+            // Targets left to someone else stay on the prompt
+            let edit = EditMessage::new()
+                .content(format!("{}\n{}", outcome, prompt_content(&remaining)))
+                .components(prompt_components(&ctx.cache, &remaining));
+            if let Err(why) = component.channel_id.edit_message(&ctx.http, component.message.id, edit).await {
+                write_error_log(format!("Error editing prompt: {why:?}"));
+            }
+        }
+
+        if let Err(why) = component.edit_response(&ctx.http, EditInteractionResponse::new().content(outcome)).await {
+            write_error_log(format!("Error answering prompt interaction: {why:?}"));
+        }
+    }
+
+    /// Replicates the thread to the `selected` targets and moves every other target to `answer.otherwise`.
+    /// Returns one line per target describing what happened to it.
+    pub(crate) async fn answer_targets(&self, ctx: &Context, answer: &PromptAnswer<'_>) -> String {
+        let mut outcome = Vec::new();
+        for (target, pair) in answer.targets.iter() {
+            if !answer.selected.contains(&target.id) {
+                match self.get_access().and_then(|db| db.transition_replication_reply(target.id, answer.otherwise, answer.actor_id, None)) {
+                    Ok(_) => outcome.push(format!("Not replicated to {}", target_label(&ctx.cache, pair))),
+                    Err(err) => write_error_log(format!("Error updating replication reply: {}", err.message)),
+                }
+                continue;
+            }
+
+            // Claiming the request first makes a concurrent answer fail instead of replicating twice
+            if let Err(err) = self.get_access().and_then(|db| db.transition_replication_reply(target.id, ReplicationStatus::Accepted, answer.actor_id, None)) {
+                write_error_log(format!("Error updating replication reply: {}", err.message));
+                continue;
+            }
+
+            let to_thread = match self.replicate_thread(ctx, answer.guild_id, answer.thread_id, target.id, pair).await {
+                Ok(thread) => thread.id,
+                Err(err) => {
+                    write_error_log(err.message.clone());
+                    outcome.push(format!("Could not replicate to {}: {}", target_label(&ctx.cache, pair), err.message));
+                    if let Err(err) = self.get_access().and_then(|db| db.transition_replication_reply(target.id, ReplicationStatus::Declined, answer.actor_id, Some(err.message))) {
+                        write_error_log(format!("Error updating replication reply: {}", err.message));
+                    }
+                    continue;
+                }
+            };
+            outcome.push(format!("Replicating to https://discord.com/channels/{}/{}", pair.to_guild, to_thread));
+
+            // Messages posted before the request was accepted
+            let dto = ReplicationBackfillJobData {
+                from_guild: answer.guild_id,
+                from_thread: answer.thread_id.get() as i64,
+                to_guild: pair.to_guild,
+                to_thread: to_thread.get() as i64,
+            };
+            if let Err(err) = self.start_backfill(ctx, dto) {
+                write_error_log(format!("Error starting backfill of thread {}: {}", answer.thread_id, err.message));
+            }
+        }
+
+        outcome.join("\n")
+    }
+
+    // Replaces the prompt with its final state, without buttons.
+    pub(crate) async fn close_prompt(&self, ctx: &Context, thread_id: ChannelId, message_id: MessageId, content: String) {
+        let edit = EditMessage::new().content(content).components(Vec::new());
+        if let Err(why) = thread_id.edit_message(&ctx.http, message_id, edit).await {
+            write_error_log(format!("Error editing prompt: {why:?}"));
+        }
+    }
+
+    async fn prompt_feedback(&self, ctx: &Context, component: &ComponentInteraction, content: &str) {
+        let response = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content(content).ephemeral(true));
+        if let Err(why) = component.create_response(&ctx.http, response).await {
+            write_error_log(format!("Error answering prompt interaction: {why:?}"));
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use serenity::all::{ChannelId, Context, CreateEmbed, EditMessage, EditWebhookMessage, MessageId, Reaction, ReactionType};
+use crate::errors::{AppError, ErrorType};
+use crate::handler::db_access::{ReplicationMessagePair, ReplicationReactionCount};
+use crate::handler::Handler;
+use crate::log::{write_error_log, write_info_log};
+
+// One `emoji count` entry per emoji still used remotely, custom emojis are stored as `name:id`.
+fn reaction_summary(counts: &[ReplicationReactionCount]) -> Option<String> {
+    let entries: Vec<String> = counts.iter()
+        .filter(|c| c.count > 0)
+        .map(|c| if c.emoji.contains(':') { format!("<:{}> {}", c.emoji, c.count) } else { format!("{} {}", c.emoji, c.count) })
+        .collect();
+
+    if entries.is_empty() {
+        return None;
+    }
+
+    Some(format!("Reactions elsewhere: {}", entries.join("  ")))
+}
+
+impl Handler {
+    /// Mirrors a reaction added to (or removed from) a replicated message onto its counterparts.
+    ///
+    /// The bot can only react once per emoji, so each counterpart keeps a count of the remote
+    /// reactions it stands for: the bot reacts on the first one and withdraws after the last one.
+    /// Copies the bot posted also show the counts in an embed.
+    pub(crate) async fn mirror_reaction(&self, ctx: &Context, reaction: &Reaction, added: bool) {
+        let _db_access = match self.get_access() {
+            Ok(access) => access,
+            Err(err) => {
+                write_error_log(err.message);
+                return;
+            }
+        };
+
+        let channel_id = reaction.channel_id.get() as i64;
+        let message_id = reaction.message_id.get() as i64;
+
+        let linked = match _db_access.get_linked_message_pairs(channel_id, message_id) {
+            Ok(linked) => linked,
+            Err(err) => {
+                write_error_log(format!("Error getting replicated messages: {}", err.message));
+                return;
+            }
+        };
+
+        for r in linked {
+            let (guild, channel, message) = if r.to_channel == channel_id && r.to_message == message_id {
+                (r.from_guild, r.from_channel, r.from_message)
+            } else if r.from_channel == channel_id && r.from_message == message_id {
+                (r.to_guild, r.to_channel, r.to_message)
+            } else {
+                // Sibling copy of the same source, through another pair
+                (r.to_guild, r.to_channel, r.to_message)
+            };
+
+            let enabled = _db_access.get_replication_forum_pair_for_threads(r.from_channel, r.to_channel)
+                .map(|pair| pair.mirror_reactions)
+                .unwrap_or(false);
+            if !enabled {
+                continue;
+            }
+
+            if let ReactionType::Custom { id, .. } = &reaction.emoji {
+                let usable = ctx.cache.guilds().iter().any(|g| {
+                    ctx.cache.guild(*g).is_some_and(|g| g.emojis.contains_key(id))
+                });
+                if !usable {
+                    write_info_log(format!("Skipping reaction {} on {}: emoji not available to the bot", reaction.emoji, message));
+                    continue;
+                }
+            }
+
+            let delta = if added { 1 } else { -1 };
+            let count = match _db_access.add_replication_reaction_count(channel, message, reaction.emoji.as_data(), delta) {
+                Ok(count) => count,
+                Err(err) => {
+                    write_error_log(format!("Error counting mirrored reaction: {}", err.message));
+                    continue;
+                }
+            };
+
+            let distant_channel = ChannelId::new(channel as u64);
+            let distant_message = MessageId::new(message as u64);
+
+            let result = if added && count == 1 {
+                distant_channel.create_reaction(&ctx.http, distant_message, reaction.emoji.clone()).await
+            } else if !added && count <= 0 {
+                distant_channel.delete_reaction(&ctx.http, distant_message, None, reaction.emoji.clone()).await
+            } else {
+                Ok(())
+            };
+
+            match result {
+                Ok(_) => {
+                    write_info_log(format!("Mirrored reaction {} on {} in guild {} ({} remote)", reaction.emoji, distant_message, guild, count.max(0)));
+                }
+                Err(why) => {
+                    write_error_log(format!("Skipping reaction {} on {}: {why:?}", reaction.emoji, distant_message));
+                }
+            }
+
+            // The source message belongs to its author, only copies can carry the counts
+            if r.to_channel == channel && r.to_message == message {
+                if let Err(err) = self.show_reaction_counts(ctx, &r).await {
+                    write_error_log(format!("Error showing reaction counts on {}: {}", distant_message, err.message));
+                }
+            }
+        }
+    }
+
+    // Replaces the embeds of a copy with the summary of its remote reactions, or removes them.
+    async fn show_reaction_counts(&self, ctx: &Context, r: &ReplicationMessagePair) -> Result<(), AppError> {
+        let counts = self.get_access()?.get_replication_reaction_counts(r.to_channel, r.to_message)?;
+        let embeds: Vec<CreateEmbed> = reaction_summary(&counts).map(|summary| CreateEmbed::new().description(summary)).into_iter().collect();
+
+        let distant_channel = ChannelId::new(r.to_channel as u64);
+        let distant_message = MessageId::new(r.to_message as u64);
+        let result = match r.webhook_id {
+            Some(webhook_id) => {
+                let edit = EditWebhookMessage::new().embeds(embeds).in_thread(distant_channel);
+                self.webhook_by_id(ctx, webhook_id).await?.edit_message(&ctx.http, distant_message, edit).await.map(|_| ())
+            }
+            None => distant_channel.edit_message(&ctx.http, distant_message, EditMessage::new().embeds(embeds)).await.map(|_| ()),
+        };
+
+        result.map_err(|why| AppError::new(format!("{why:?}").as_str(), ErrorType::DistantServer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(emoji: &str, count: i32) -> ReplicationReactionCount {
+        ReplicationReactionCount {
+            id: 1,
+            channel_id: 1,
+            message_id: 1,
+            emoji: emoji.to_string(),
+            count,
+            created_at: Default::default(),
+        }
+    }
+
+    #[test]
+    fn summary_lists_remote_counts() {
+        let counts = [count("👍", 3), count("party:123", 1), count("❤️", 0)];
+
+        assert_eq!(reaction_summary(&counts), Some("Reactions elsewhere: 👍 3  <:party:123> 1".to_string()));
+        assert_eq!(reaction_summary(&[count("👍", 0)]), None);
+        assert_eq!(reaction_summary(&[]), None);
+    }
+}
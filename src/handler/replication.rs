@@ -0,0 +1,417 @@
+use serenity::all::{Cache, ChannelId, Context, CreateForumPost, CreateMessage, ForumTagId, Guild, GuildChannel, GuildId, Message, MessageId};
+use crate::errors::{AppError, ErrorType};
+use crate::handler::attachments::{replicate_attachments, upload_limit, with_fallback_lines};
+use crate::handler::content::{no_mentions, paired_channels, sanitize_mentions};
+use crate::handler::db_access::{AttachmentFallback, OriginLabel, ReplicationChannelPair, ReplicationForumPair, ReplicationMessagePairData, ReplicationThreadPair, ReplicationThreadPairData};
+use crate::handler::handlers::replicated_content;
+use crate::handler::slash_commands::guild_name;
+use crate::handler::Handler;
+use crate::log::{write_error_log, write_info_log};
+
+// Discord accepts at most 5 tags on a forum post.
+const MAX_APPLIED_TAGS: usize = 5;
+
+const QUOTE_LENGTH: usize = 100;
+
+const REPLICATED_SUFFIX: &str = " - REPLICATED";
+
+// Copies of copies stop here, whatever the topology of the pairs.
+const MAX_HOPS: i32 = 3;
+
+/// What `Handler::forward_message` did with a message: the copies it sent, or tried to, with the
+/// guild of each, and whether a pair filter held the message back.
+pub struct Forwarded {
+    pub copies: Vec<(i64, Result<Message, AppError>)>,
+    pub filtered: bool,
+}
+
+/// Where a message first came from: itself for an original message, the recorded origin for a copy.
+pub struct MessageOrigin {
+    pub guild: i64,
+    pub channel: i64,
+    pub message: i64,
+    pub hops: i32,
+}
+
+// Title of the remote post replicating a thread named `name`, after the origin prefix if any.
+pub fn replicated_title(name: &str, prefix: Option<&str>) -> String {
+    format!("{}{}{}", prefix.unwrap_or_default(), name, REPLICATED_SUFFIX)
+}
+
+// Name of the source thread for a remote post titled `title`.
+pub fn original_title(title: &str, prefix: Option<&str>) -> String {
+    let title = prefix.and_then(|prefix| title.strip_prefix(prefix)).unwrap_or(title);
+    title.strip_suffix(REPLICATED_SUFFIX).unwrap_or(title).to_string()
+}
+
+// Tag of the target forum named after the source server, for pairs labelled with a tag.
+fn origin_tag(cache: &Cache, pair: &ReplicationForumPair) -> Option<ForumTagId> {
+    if pair.origin_label() != OriginLabel::Tag {
+        return None;
+    }
+
+    let origin = cache.guild(pair.from_guild as u64)?.name.clone();
+    forum_of(cache, pair.to_guild as u64, pair.to_forum as u64)?
+        .available_tags.iter()
+        .find(|tag| tag.name.eq_ignore_ascii_case(&origin))
+        .map(|tag| tag.id)
+}
+
+/// Prefix naming the source server in the titles of the pair's remote posts: always with
+/// `prefix`, and with `tag` when the target forum has no tag for that server.
+pub fn origin_prefix(cache: &Cache, pair: &ReplicationForumPair) -> Option<String> {
+    match pair.origin_label() {
+        OriginLabel::Off => None,
+        OriginLabel::Tag if origin_tag(cache, pair).is_some() => None,
+        _ => Some(format!("[{}] ", guild_name(cache, pair.from_guild))),
+    }
+}
+
+// Short quote of a replied-to message that has no counterpart on the other side.
+fn quote_reference(referenced: &Message) -> String {
+    let mut excerpt: String = referenced.content.chars().take(QUOTE_LENGTH).collect();
+    if referenced.content.chars().count() > QUOTE_LENGTH {
+        excerpt.push('…');
+    }
+
+    format!("> **{}**: {}\n", referenced.author.name, excerpt.replace('\n', " "))
+}
+
+fn forum_of(cache: &Cache, guild_id: u64, forum_id: u64) -> Option<GuildChannel> {
+    cache.guild(guild_id).and_then(|g| g.channels.get(&ChannelId::new(forum_id)).cloned())
+}
+
+// Tags of the source thread that the target forum also has, matched by name.
+fn mapped_tags(cache: &Cache, source_thread: &GuildChannel, pair: &ReplicationForumPair) -> Vec<ForumTagId> {
+    let source_forum = source_thread.parent_id.and_then(|parent| forum_of(cache, source_thread.guild_id.get(), parent.get()));
+    let target_forum = forum_of(cache, pair.to_guild as u64, pair.to_forum as u64);
+    let (source_forum, target_forum) = match (source_forum, target_forum) {
+        (Some(source_forum), Some(target_forum)) => (source_forum, target_forum),
+        _ => return Vec::new(),
+    };
+
+    source_thread.applied_tags.iter()
+        .filter_map(|applied| source_forum.available_tags.iter().find(|tag| tag.id == *applied))
+        .filter_map(|tag| target_forum.available_tags.iter().find(|target| target.name.eq_ignore_ascii_case(&tag.name)))
+        .map(|tag| tag.id)
+        .take(MAX_APPLIED_TAGS)
+        .collect()
+}
+
+impl Handler {
+    pub(crate) fn message_origin(&self, guild_id: i64, msg: &Message) -> Result<MessageOrigin, AppError> {
+        let source = self.get_access()?.get_replication_message_source(msg.channel_id.get() as i64, msg.id.get() as i64)?;
+
+        Ok(match source {
+            Some(source) => MessageOrigin {
+                guild: source.origin_guild,
+                channel: source.origin_channel,
+                message: source.origin_message,
+                hops: source.hops,
+            },
+            None => MessageOrigin {
+                guild: guild_id,
+                channel: msg.channel_id.get() as i64,
+                message: msg.id.get() as i64,
+                hops: 0,
+            },
+        })
+    }
+
+    /// Whether the bot posted `msg`, itself or through one of its replication webhooks.
+    pub(crate) fn produced_by_bot(&self, ctx: &Context, msg: &Message) -> bool {
+        if msg.author.id == ctx.cache.current_user().id {
+            return true;
+        }
+
+        msg.webhook_id.is_some_and(|webhook_id| {
+            self.get_access()
+                .and_then(|db| db.get_replication_webhook_by_webhook_id(webhook_id.get() as i64))
+                .is_ok()
+        })
+    }
+
+    /// Loop and echo protection: a message never goes back to the channel it originated in, nor
+    /// to a channel that already has a copy of its origin, nor further than `MAX_HOPS` copies away.
+    pub(crate) fn may_replicate(&self, origin: &MessageOrigin, to_channel: i64) -> bool {
+        if origin.hops >= MAX_HOPS || origin.channel == to_channel {
+            return false;
+        }
+
+        self.get_access()
+            .and_then(|db| db.has_replication_message_copy(origin.channel, origin.message, to_channel))
+            .is_ok_and(|copied| !copied)
+    }
+
+    /// Replicates `msg`, posted in `guild_id`, along every thread and channel pair of its channel
+    /// allowed by the loop protection and, for thread pairs, by the filters of the forum pair.
+    pub(crate) async fn forward_message(&self, ctx: &Context, guild_id: i64, msg: &Message) -> Result<Forwarded, AppError> {
+        let _db_access = self.get_access()?;
+        let threads = _db_access.get_replication_thread_pairs(guild_id, msg.channel_id.get() as i64).unwrap_or_default();
+        let channels = _db_access.get_replication_channel_pairs(guild_id, msg.channel_id.get() as i64).unwrap_or_default();
+        let mut forwarded = Forwarded { copies: Vec::new(), filtered: false };
+        if threads.is_empty() && channels.is_empty() {
+            write_info_log("No replication pair found".to_string());
+            return Ok(forwarded);
+        }
+
+        let origin = self.message_origin(guild_id, msg)?;
+        // Thread pairs of paused requests are not returned, see `get_replication_thread_pairs`
+        for f in threads {
+            if !self.may_replicate(&origin, f.to_thread) {
+                write_info_log(format!("Not replicating {} back towards {}: origin {}, {} hop(s)", msg.id, f.to_thread, origin.channel, origin.hops));
+                continue;
+            }
+
+            let pair = _db_access.get_replication_forum_pair_for_threads(f.from_thread, f.to_thread);
+            if let Some(reason) = pair.ok().and_then(|pair| self.filtered(ctx, guild_id, msg, pair.id)) {
+                write_info_log(format!("Message {} not replicated to {}: {}", msg.id, f.to_thread, reason));
+                forwarded.filtered = true;
+                continue;
+            }
+
+            forwarded.copies.push((f.to_guild, self.replicate_message(ctx, msg, &f).await));
+        }
+        for c in channels {
+            if self.may_replicate(&origin, c.to_channel) {
+                forwarded.copies.push((c.to_guild, self.replicate_to_channel(ctx, msg, &c).await));
+            } else {
+                write_info_log(format!("Not replicating {} back towards {}: origin {}, {} hop(s)", msg.id, c.to_channel, origin.channel, origin.hops));
+            }
+        }
+
+        Ok(forwarded)
+    }
+
+    /// Forwards copies the bot just sent, with their guild, along the pairs of their own channel
+    /// and so on down the chain. This happens here rather than on the gateway echo of each copy,
+    /// which can arrive before the copy is recorded and would then look like any bot message.
+    pub(crate) async fn forward_copies(&self, ctx: &Context, mut copies: Vec<(i64, Message)>) {
+        while let Some((guild_id, copy)) = copies.pop() {
+            let forwarded = match self.forward_message(ctx, guild_id, &copy).await {
+                Ok(forwarded) => forwarded,
+                Err(err) => {
+                    write_error_log(format!("Error forwarding copy {}: {}", copy.id, err.message));
+                    continue;
+                }
+            };
+
+            for (to_guild, result) in forwarded.copies {
+                match result {
+                    Ok(next) => copies.push((to_guild, next)),
+                    Err(err) => write_error_log(format!("Error forwarding copy {}: {}", copy.id, err.message)),
+                }
+            }
+        }
+    }
+
+    /// Creates the remote forum post for an accepted replication and pairs both threads, so that
+    /// `Handler::message` forwards messages in each direction.
+    ///
+    /// The remote post starts with a copy of the source starter message (author, content and
+    /// attachments) and carries the source tags that exist in the target forum. Several sources can
+    /// feed the same forum: the thread pairs tie each remote post to its own source thread only.
+    pub(crate) async fn replicate_thread(&self, ctx: &Context, guild_id: i64, thread_id: ChannelId, replication_reply_id: i64, pair: &ReplicationForumPair) -> Result<GuildChannel, AppError> {
+        let source_thread = thread_id.to_channel(&ctx.http).await.ok().and_then(|channel| channel.guild());
+        let current_thread_name = source_thread.as_ref().map_or("Replicated thread".to_string(), |thread| thread.name.clone());
+
+        // The starter message of a forum post shares the id of its thread
+        let starter = thread_id.message(&ctx.http, MessageId::new(thread_id.get())).await.ok();
+
+        let init_message = match &starter {
+            Some(starter) => {
+                let source_guild = GuildId::new(guild_id as u64);
+                let paired = paired_channels(&self.get_access()?, guild_id, pair.to_guild, &starter.content);
+                let body = sanitize_mentions(&ctx.cache, source_guild, &starter.content, &paired);
+
+                let limit = ctx.cache.guild(pair.to_guild as u64).map(|g| upload_limit(g.premium_tier)).unwrap_or(upload_limit(Default::default()));
+                let (files, fallback_lines) = replicate_attachments(&starter.attachments, limit, pair.attachment_fallback()).await;
+
+                let content = with_fallback_lines(&replicated_content(&starter.author.name, &body), &fallback_lines);
+
+                CreateMessage::new().content(content).add_files(files).allowed_mentions(no_mentions())
+            }
+            None => CreateMessage::new().content(format!("FIRST MSG - {}", replicated_title(&current_thread_name, None))),
+        };
+
+        // Forums fed by several sources tell where each post comes from
+        let title = replicated_title(&current_thread_name, origin_prefix(&ctx.cache, pair).as_deref());
+        let mut tags: Vec<ForumTagId> = origin_tag(&ctx.cache, pair).into_iter().collect();
+        if let Some(source_thread) = &source_thread {
+            for tag in mapped_tags(&ctx.cache, source_thread, pair) {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        }
+        tags.truncate(MAX_APPLIED_TAGS);
+
+        let forum_post = CreateForumPost::new(title, init_message).set_applied_tags(tags);
+
+        let new_thread = ChannelId::new(pair.to_forum as u64)
+            .create_forum_post(&ctx.http, forum_post)
+            .await
+            .map_err(|why| AppError::new(format!("Error creating thread: {why}").as_str(), ErrorType::DistantServer))?;
+
+        let first = ReplicationThreadPairData {
+            from_guild: guild_id,
+            from_thread: thread_id.get() as i64,
+            to_guild: pair.to_guild,
+            to_thread: new_thread.id.get() as i64,
+            replication_reply_id,
+        };
+        let second = ReplicationThreadPairData {
+            from_guild: pair.to_guild,
+            from_thread: new_thread.id.get() as i64,
+            to_guild: guild_id,
+            to_thread: thread_id.get() as i64,
+            replication_reply_id,
+        };
+
+        let _db_access = self.get_access()?;
+        _db_access.create_replication_thread_pair(first)?;
+        _db_access.create_replication_thread_pair(second)?;
+
+        // Edits, deletions and reactions of the starter message follow it like any replicated message
+        if starter.is_some() {
+            if let Err(err) = _db_access.create_replication_message_pair(ReplicationMessagePairData {
+                from_guild: guild_id,
+                from_channel: thread_id.get() as i64,
+                from_message: thread_id.get() as i64,
+                to_guild: pair.to_guild,
+                to_channel: new_thread.id.get() as i64,
+                to_message: new_thread.id.get() as i64,
+                webhook_id: None,
+                origin_guild: guild_id,
+                origin_channel: thread_id.get() as i64,
+                origin_message: thread_id.get() as i64,
+                hops: 1,
+            }) {
+                write_error_log(format!("Error recording replicated starter message: {}", err.message));
+            }
+        }
+
+        write_info_log(format!("Thread {} replicated to {} in guild {}", thread_id, new_thread.id, pair.to_guild));
+
+        Ok(new_thread)
+    }
+
+    /// Sends a copy of `msg` into the paired thread `f` and records the mapping between both messages.
+    /// Replies point at the counterpart of the referenced message, mentions are neutralised and
+    /// attachments over the target's upload limit follow the pair's fallback.
+    pub(crate) async fn replicate_message(&self, ctx: &Context, msg: &Message, f: &ReplicationThreadPair) -> Result<Message, AppError> {
+        let guild = match ctx.cache.guild(f.to_guild as u64) {
+            Some(guild) => guild.clone(),
+            None => return Err(AppError::new(format!("Guild not found: {}", f.to_guild).as_str(), ErrorType::NotFound)),
+        };
+
+        let distant_thread = match guild.threads.iter().find(|t| t.id == f.to_thread as u64) {
+            Some(thread) => thread.clone(),
+            None => return Err(AppError::new(format!("Thread not found: {}", f.to_thread).as_str(), ErrorType::NotFound)),
+        };
+
+        let pair = self.get_access()?.get_replication_forum_pair_for_threads(f.from_thread, f.to_thread).ok();
+
+        self.send_replica(ctx, msg, f.from_guild, &guild, &distant_thread, pair.as_ref()).await
+    }
+
+    /// Sends a copy of `msg` into the paired text channel `c`, with the same formatting as thread
+    /// replication. Channel pairs have no settings: attachments fall back to links and the bot posts.
+    pub(crate) async fn replicate_to_channel(&self, ctx: &Context, msg: &Message, c: &ReplicationChannelPair) -> Result<Message, AppError> {
+        let guild = match ctx.cache.guild(c.to_guild as u64) {
+            Some(guild) => guild.clone(),
+            None => return Err(AppError::new(format!("Guild not found: {}", c.to_guild).as_str(), ErrorType::NotFound)),
+        };
+
+        let distant_channel = match guild.channels.get(&ChannelId::new(c.to_channel as u64)) {
+            Some(channel) => channel.clone(),
+            None => return Err(AppError::new(format!("Channel not found: {}", c.to_channel).as_str(), ErrorType::NotFound)),
+        };
+
+        self.send_replica(ctx, msg, c.from_guild, &guild, &distant_channel, None).await
+    }
+
+    /// Counterpart in `distant` of `referenced`, the message replied to as seen from the channel the
+    /// copy is made from, when it was replicated. Webhooks cannot reply, so they never get one.
+    pub(crate) fn reply_counterpart(&self, referenced: Option<(ChannelId, MessageId)>, distant: ChannelId, use_webhook: bool) -> Result<Option<MessageId>, AppError> {
+        Ok(match referenced {
+            Some((channel, referenced)) if !use_webhook => self.get_access()?
+                .get_replicated_counterpart(channel.get() as i64, referenced.get() as i64, distant.get() as i64)
+                .ok()
+                .map(|m| MessageId::new(m as u64)),
+            _ => None,
+        })
+    }
+
+    /// Text of the copy of `msg`, for new messages and edits alike. A copy without a `reply_to`
+    /// counterpart quotes the message `msg` replies to instead.
+    pub(crate) fn replica_body(&self, ctx: &Context, msg: &Message, from_guild: i64, to_guild: i64, reply_to: Option<MessageId>, use_webhook: bool) -> Result<String, AppError> {
+        let quote = match (&msg.referenced_message, reply_to) {
+            (Some(referenced), None) => quote_reference(referenced),
+            _ => String::new(),
+        };
+
+        let source_guild = GuildId::new(from_guild as u64);
+        let paired = paired_channels(&self.get_access()?, from_guild, to_guild, &msg.content);
+        let quote = sanitize_mentions(&ctx.cache, source_guild, &quote, &paired);
+        let body = sanitize_mentions(&ctx.cache, source_guild, &msg.content, &paired);
+
+        // Webhooks carry the author's name and avatar, so the prefix is only needed for the bot.
+        // A copy the bot posted already starts with the name of the original author.
+        let attributed = msg.author.id == ctx.cache.current_user().id;
+        let content = if use_webhook || attributed {
+            format!("{}{}", quote, body)
+        } else {
+            format!("{}{}", quote, replicated_content(&msg.author.name, &body))
+        };
+
+        Ok(content)
+    }
+
+    // Settings come from the forum pair behind the thread pair, defaults apply without one.
+    async fn send_replica(&self, ctx: &Context, msg: &Message, from_guild: i64, guild: &Guild, distant: &GuildChannel, pair: Option<&ReplicationForumPair>) -> Result<Message, AppError> {
+        let to_guild = guild.id.get() as i64;
+        let fallback = pair.map(|p| p.attachment_fallback()).unwrap_or(AttachmentFallback::Link);
+        let use_webhook = pair.is_some_and(|p| p.use_webhook);
+        let (files, fallback_lines) = replicate_attachments(&msg.attachments, upload_limit(guild.premium_tier), fallback).await;
+
+        let referenced = msg.message_reference.as_ref().and_then(|r| r.message_id).map(|m| (msg.channel_id, m));
+        let reply_to = self.reply_counterpart(referenced, distant.id, use_webhook)?;
+        let body = self.replica_body(ctx, msg, from_guild, to_guild, reply_to, use_webhook)?;
+        let content = with_fallback_lines(&body, &fallback_lines);
+
+        let replicated = if use_webhook {
+            self.execute_in_thread(ctx, distant, msg, content, files).await?
+        } else {
+            let mut builder = CreateMessage::new().content(content).add_files(files).allowed_mentions(no_mentions());
+            if let Some(reply_to) = reply_to {
+                builder = builder.reference_message((distant.id, reply_to));
+            }
+
+            distant.id.send_message(&ctx.http, builder).await
+                .map_err(|why| AppError::new(format!("Error sending message: {why:?}").as_str(), ErrorType::DistantServer))?
+        };
+
+        // A copy of a copy keeps pointing at the first message of the chain
+        let recorded = self.message_origin(from_guild, msg).and_then(|origin| {
+            self.get_access()?.create_replication_message_pair(ReplicationMessagePairData {
+                from_guild,
+                from_channel: msg.channel_id.get() as i64,
+                from_message: msg.id.get() as i64,
+                to_guild,
+                to_channel: replicated.channel_id.get() as i64,
+                to_message: replicated.id.get() as i64,
+                webhook_id: replicated.webhook_id.map(|w| w.get() as i64),
+                origin_guild: origin.guild,
+                origin_channel: origin.channel,
+                origin_message: origin.message,
+                hops: origin.hops + 1,
+            })
+        });
+        if let Err(err) = recorded {
+            write_error_log(format!("Error recording replicated message: {}", err.message));
+        }
+
+        Ok(replicated)
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::time::Duration;
+use serenity::all::{ChannelId, Context, CreateMessage, MessageId};
+use crate::handler::db_access::{ApprovalPolicy, ExpiryPolicy, ReplicationForumPair, ReplicationReply, ReplicationStatus};
+use crate::handler::prompts::PromptAnswer;
+use crate::handler::Handler;
+use crate::log::{write_error_log, write_info_log};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_REMINDER_MINUTES: i32 = 24 * 60;
+const DEFAULT_EXPIRY_MINUTES: i32 = 3 * 24 * 60;
+
+fn minutes_from_env(name: &str, default: i32) -> i32 {
+    env::var(name).ok().and_then(|v| v.parse::<i32>().ok()).filter(|v| *v > 0).unwrap_or(default)
+}
+
+// Unanswered targets grouped by the thread their prompt was posted in.
+fn by_thread(pending: Vec<(ReplicationReply, ReplicationForumPair)>) -> BTreeMap<(i64, i64), Vec<(ReplicationReply, ReplicationForumPair)>> {
+    let mut threads: BTreeMap<(i64, i64), Vec<(ReplicationReply, ReplicationForumPair)>> = BTreeMap::new();
+    for (reply, pair) in pending {
+        threads.entry((reply.guild_id, reply.channel_id)).or_default().push((reply, pair));
+    }
+
+    threads
+}
+
+impl Handler {
+    /// Periodically reminds thread owners of unanswered pairing prompts, then resolves the prompts
+    /// still unanswered once they expire according to each pair's `on_prompt_expiry` policy.
+    ///
+    /// Delays are read from `REPLICATION_PROMPT_REMINDER_MINUTES` and `REPLICATION_PROMPT_EXPIRY_MINUTES`.
+    pub(crate) async fn run_prompt_scheduler(&self, ctx: Context) {
+        let reminder_minutes = minutes_from_env("REPLICATION_PROMPT_REMINDER_MINUTES", DEFAULT_REMINDER_MINUTES);
+        let expiry_minutes = minutes_from_env("REPLICATION_PROMPT_EXPIRY_MINUTES", DEFAULT_EXPIRY_MINUTES);
+        write_info_log(format!("Prompt scheduler started: reminder after {} min, expiry after {} min", reminder_minutes, expiry_minutes));
+
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            self.expire_prompts(&ctx, expiry_minutes).await;
+            self.remind_prompts(&ctx, reminder_minutes).await;
+        }
+    }
+
+    async fn expire_prompts(&self, ctx: &Context, expiry_minutes: i32) {
+        let pending = match self.get_access().and_then(|db| db.get_pending_replication_replies(expiry_minutes)) {
+            Ok(pending) => pending,
+            Err(err) => {
+                write_error_log(format!("Error getting expired replication prompts: {}", err.message));
+                return;
+            }
+        };
+
+        for ((guild_id, channel_id), targets) in by_thread(pending) {
+            let thread_id = ChannelId::new(channel_id as u64);
+            let selected: Vec<i64> = targets.iter()
+                .filter(|(_, pair)| pair.expiry_policy() == ExpiryPolicy::Accept)
+                .map(|(reply, _)| reply.id)
+                .collect();
+
+            let answer = PromptAnswer {
+                guild_id,
+                thread_id,
+                targets: &targets,
+                selected: &selected,
+                otherwise: ReplicationStatus::Expired,
+                actor_id: None,
+            };
+            let outcome = self.answer_targets(ctx, &answer).await;
+            write_info_log(format!("Replication request of thread {} expired: {}", channel_id, outcome));
+
+            for (reply, _) in targets.iter() {
+                if let Ok(Some(entry)) = self.get_access().and_then(|db| db.get_replication_queue_by_reply(reply.id)) {
+                    self.close_queue_entry(ctx, &entry, format!("Replication request #{} expired without an answer", entry.id)).await;
+                }
+            }
+
+            // The owner prompt only shows targets that are not queued for moderators
+            let prompted = targets.iter().find(|(_, pair)| pair.approval_policy() != ApprovalPolicy::TargetModerators);
+            if let Some(message_id) = prompted.and_then(|(reply, _)| reply.message_id) {
+                let content = format!("This replication request expired without an answer.\n{}", outcome);
+                self.close_prompt(ctx, thread_id, MessageId::new(message_id as u64), content).await;
+            }
+        }
+    }
+
+    async fn remind_prompts(&self, ctx: &Context, reminder_minutes: i32) {
+        let pending = match self.get_access().and_then(|db| db.get_pending_replication_replies(reminder_minutes)) {
+            Ok(pending) => pending,
+            Err(err) => {
+                write_error_log(format!("Error getting unanswered replication prompts: {}", err.message));
+                return;
+            }
+        };
+
+        for ((guild_id, channel_id), targets) in by_thread(pending) {
+            // Requests queued for the target's moderators are not the owner's to answer
+            let targets: Vec<_> = targets.into_iter()
+                .filter(|(_, pair)| pair.approval_policy() != ApprovalPolicy::TargetModerators)
+                .collect();
+            let reply = match targets.first() {
+                Some((reply, _)) if reply.reminded_at.is_none() => reply,
+                _ => continue,
+            };
+
+            let mut content = if targets.iter().all(|(_, pair)| pair.approval_policy() == ApprovalPolicy::ModeratorOnly) {
+                "This post is still waiting for a moderator's answer to be replicated".to_string()
+            } else {
+                format!("<@{}>, this post is still waiting for your answer to be replicated", reply.message_owner)
+            };
+            if let Some(message_id) = reply.message_id {
+                content.push_str(&format!(": https://discord.com/channels/{}/{}/{}", guild_id, channel_id, message_id));
+            }
+
+            match ChannelId::new(channel_id as u64).send_message(&ctx.http, CreateMessage::new().content(content)).await {
+                Ok(_) => write_info_log(format!("Reminded owner of thread {} about its replication prompt", channel_id)),
+                Err(why) => write_error_log(format!("Error sending prompt reminder: {why:?}")),
+            }
+
+            // Only remind once, even if the reminder could not be sent
+            if let Err(err) = self.get_access().and_then(|db| db.update_replication_reply_reminded_at(guild_id, channel_id)) {
+                write_error_log(format!("Error updating replication reply: {}", err.message));
+            }
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use serenity::all::{
+    Cache, ChannelId, ChannelType, CommandDataOption, CommandDataOptionValue, CommandInteraction, CommandOptionType, Context,
+    CreateAutocompleteResponse, CreateCommand, CreateCommandOption, CreateInteractionResponse, CreateInteractionResponseMessage,
+    EditInteractionResponse, GuildId, Permissions, UserId,
+};
+use crate::errors::ErrorType;
+use crate::handler::db_access::{ReplicationChannelPair, ReplicationForumPair, ReplicationForumPairData};
+use crate::handler::Handler;
+use crate::handler::validation::validate_forum_pair;
+use crate::log::{write_error_log, write_info_log};
+
+const MAX_CHOICES: usize = 25;
+
+// Status changes listed by `/pair info`.
+const STATUS_HISTORY_LENGTH: i64 = 5;
+
+pub fn pair_command() -> CreateCommand {
+    let pair_id = || CreateCommandOption::new(CommandOptionType::Integer, "pair_id", "Replication pair")
+        .required(true)
+        .set_autocomplete(true);
+
+    CreateCommand::new("pair")
+        .description("Manage forum replication pairs")
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "create", "Replicate a forum of this server into another server")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Channel, "source_forum", "Forum of this server")
+                        .channel_types(vec![ChannelType::Forum])
+                        .required(true),
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "target_guild", "Server to replicate to")
+                        .required(true)
+                        .set_autocomplete(true),
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "target_forum", "Forum of the target server")
+                        .required(true)
+                        .set_autocomplete(true),
+                ),
+        )
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "list", "List the replication pairs of this server"))
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "remove", "Remove a replication pair")
+                .add_sub_option(pair_id())
+                .add_sub_option(CreateCommandOption::new(CommandOptionType::Boolean, "cascade", "Also drop its replicated threads")),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "info", "Show the settings of a replication pair")
+                .add_sub_option(pair_id()),
+        )
+}
+
+// Sub command name and its options, for a `/pair <sub command>` interaction.
+fn sub_command(command: &CommandInteraction) -> Option<(&str, &[CommandDataOption])> {
+    command.data.options.first().and_then(|o| match &o.value {
+        CommandDataOptionValue::SubCommand(options) => Some((o.name.as_str(), options.as_slice())),
+        _ => None,
+    })
+}
+
+// Value of a string option, including a partially typed autocomplete value.
+fn option_str<'a>(options: &'a [CommandDataOption], name: &str) -> Option<&'a str> {
+    options.iter().find(|o| o.name == name).and_then(|o| match &o.value {
+        CommandDataOptionValue::String(value) => Some(value.as_str()),
+        CommandDataOptionValue::Autocomplete { value, .. } => Some(value.as_str()),
+        _ => None,
+    })
+}
+
+fn option_int(options: &[CommandDataOption], name: &str) -> Option<i64> {
+    options.iter().find(|o| o.name == name).and_then(|o| o.value.as_i64())
+}
+
+fn option_channel(options: &[CommandDataOption], name: &str) -> Option<ChannelId> {
+    options.iter().find(|o| o.name == name).and_then(|o| o.value.as_channel_id())
+}
+
+pub fn guild_name(cache: &Cache, guild_id: i64) -> String {
+    cache.guild(guild_id as u64).map_or(format!("{}", guild_id), |g| g.name.clone())
+}
+
+pub fn channel_name(cache: &Cache, guild_id: i64, channel_id: i64) -> String {
+    cache.guild(guild_id as u64)
+        .and_then(|g| g.channels.get(&ChannelId::new(channel_id as u64)).map(|c| c.name.clone()))
+        .unwrap_or_else(|| format!("{}", channel_id))
+}
+
+pub fn describe_pair(cache: &Cache, pair: &ReplicationForumPair) -> String {
+    format!(
+        "#{}: {} / {} -> {} / {}{}",
+        pair.id,
+        guild_name(cache, pair.from_guild),
+        channel_name(cache, pair.from_guild, pair.from_forum),
+        guild_name(cache, pair.to_guild),
+        channel_name(cache, pair.to_guild, pair.to_forum),
+        if pair.broken_reason.is_some() { " (broken)" } else { "" },
+    )
+}
+
+pub fn describe_channel_pair(cache: &Cache, pair: &ReplicationChannelPair) -> String {
+    format!(
+        "channel #{}: {} / {} -> {} / {}",
+        pair.id,
+        guild_name(cache, pair.from_guild),
+        channel_name(cache, pair.from_guild, pair.from_channel),
+        guild_name(cache, pair.to_guild),
+        channel_name(cache, pair.to_guild, pair.to_channel),
+    )
+}
+
+fn ephemeral(content: impl Into<String>) -> CreateInteractionResponse {
+    CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content(content).ephemeral(true))
+}
+
+impl Handler {
+    pub(crate) async fn register_slash_commands(&self, ctx: &Context, guild_id: GuildId) {
+        match guild_id.set_commands(&ctx.http, vec![pair_command()]).await {
+            Ok(_) => write_info_log(format!("Slash commands registered in guild {}", guild_id)),
+            Err(why) => write_error_log(format!("Error registering slash commands in guild {}: {why:?}", guild_id)),
+        }
+    }
+
+    pub(crate) async fn handle_pair_command(&self, ctx: &Context, command: &CommandInteraction) {
+        let guild_id = match command.guild_id {
+            Some(guild_id) => guild_id.get() as i64,
+            None => return,
+        };
+
+        let content = match sub_command(command) {
+            Some(("create", options)) => {
+                // Validation calls Discord, answer within the interaction deadline and edit the result in
+                let deferred = CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new().ephemeral(true));
+                if let Err(why) = command.create_response(&ctx.http, deferred).await {
+                    write_error_log(format!("Error deferring /pair create: {why:?}"));
+                    return;
+                }
+
+                let content = self.pair_create(ctx, guild_id, command.user.id, options).await;
+                if let Err(why) = command.edit_response(&ctx.http, EditInteractionResponse::new().content(content)).await {
+                    write_error_log(format!("Error responding to /pair create: {why:?}"));
+                }
+                return;
+            }
+            Some(("list", _)) => self.pair_list(ctx, guild_id),
+            Some(("remove", options)) => self.pair_remove(ctx, guild_id, options),
+            Some(("info", options)) => self.pair_info(ctx, guild_id, options),
+            _ => "Unknown command".to_string(),
+        };
+
+        if let Err(why) = command.create_response(&ctx.http, ephemeral(content)).await {
+            write_error_log(format!("Error responding to /pair: {why:?}"));
+        }
+    }
+
+    pub(crate) async fn handle_pair_autocomplete(&self, ctx: &Context, command: &CommandInteraction) {
+        let guild_id = match command.guild_id {
+            Some(guild_id) => guild_id,
+            None => return,
+        };
+        let (focused, typed) = match command.data.autocomplete() {
+            Some(focused) => (focused.name.to_string(), focused.value.to_lowercase()),
+            None => return,
+        };
+        let options = sub_command(command).map(|(_, options)| options).unwrap_or_default();
+
+        let mut string_choices: Vec<(String, String)> = Vec::new();
+        let mut int_choices: Vec<(String, i64)> = Vec::new();
+        match focused.as_str() {
+            "target_guild" => {
+                for g in ctx.cache.guilds().into_iter().filter(|g| *g != guild_id) {
+                    let name = guild_name(&ctx.cache, g.get() as i64);
+                    if name.to_lowercase().contains(&typed) {
+                        string_choices.push((name, g.to_string()));
+                    }
+                }
+            }
+            "target_forum" => {
+                let target = option_str(options, "target_guild").and_then(|g| g.parse::<u64>().ok()).filter(|g| *g != 0);
+                if let Some(guild) = target.and_then(|g| ctx.cache.guild(g)) {
+                    for channel in guild.channels.values().filter(|c| c.kind == ChannelType::Forum) {
+                        if channel.name.to_lowercase().contains(&typed) {
+                            string_choices.push((channel.name.clone(), channel.id.to_string()));
+                        }
+                    }
+                }
+            }
+            "pair_id" => {
+                if let Ok(pairs) = self.get_access().and_then(|db| db.get_replication_forum_pairs_by_guild(guild_id.get() as i64)) {
+                    for pair in pairs {
+                        let description = describe_pair(&ctx.cache, &pair);
+                        if description.to_lowercase().contains(&typed) {
+                            int_choices.push((description, pair.id));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        // Discord rejects more than 25 choices
+        let mut response = CreateAutocompleteResponse::new();
+        for (name, value) in string_choices.into_iter().take(MAX_CHOICES) {
+            response = response.add_string_choice(name, value);
+        }
+        for (name, value) in int_choices.into_iter().take(MAX_CHOICES) {
+            response = response.add_int_choice(name, value);
+        }
+
+        if let Err(why) = command.create_response(&ctx.http, CreateInteractionResponse::Autocomplete(response)).await {
+            write_error_log(format!("Error responding to /pair autocomplete: {why:?}"));
+        }
+    }
+
+    async fn pair_create(&self, ctx: &Context, guild_id: i64, user_id: UserId, options: &[CommandDataOption]) -> String {
+        let from_forum = option_channel(options, "source_forum");
+        let to_guild = option_str(options, "target_guild").and_then(|v| v.parse::<i64>().ok());
+        let to_forum = option_str(options, "target_forum").and_then(|v| v.parse::<i64>().ok());
+
+        let (from_forum, to_guild, to_forum) = match (from_forum, to_guild, to_forum) {
+            (Some(from_forum), Some(to_guild), Some(to_forum)) => (from_forum.get() as i64, to_guild, to_forum),
+            _ => return "Pick the target server and forum from the suggestions".to_string(),
+        };
+
+        if let Err(err) = validate_forum_pair(ctx, user_id, guild_id, from_forum, to_guild, to_forum).await {
+            return format!("Cannot create replication pair: {}", err.message);
+        }
+
+        let created = self.get_access().and_then(|db| db.create_replication_forum_pair(ReplicationForumPairData {
+            from_guild: guild_id,
+            from_forum,
+            to_guild,
+            to_forum,
+        }));
+
+        match created {
+            Ok(pair) => {
+                write_info_log(format!("Replication pair created {:?}", pair));
+                format!("Replication pair created: {}", describe_pair(&ctx.cache, &pair))
+            }
+            Err(err) => format!("Error creating replication pair: {}", err.message),
+        }
+    }
+
+    fn pair_list(&self, ctx: &Context, guild_id: i64) -> String {
+        match self.get_access().and_then(|db| db.get_replication_forum_pairs_by_guild(guild_id)) {
+            Ok(pairs) if pairs.is_empty() => "No replication pair in this server".to_string(),
+            Ok(pairs) => pairs.iter().map(|p| describe_pair(&ctx.cache, p)).collect::<Vec<String>>().join("\n"),
+            Err(err) => format!("Error listing replication pairs: {}", err.message),
+        }
+    }
+
+    fn pair_remove(&self, ctx: &Context, guild_id: i64, options: &[CommandDataOption]) -> String {
+        let pair = match self.pair_of_guild(guild_id, options) {
+            Ok(pair) => pair,
+            Err(message) => return message,
+        };
+
+        let cascade = options.iter().find(|o| o.name == "cascade").and_then(|o| o.value.as_bool()).unwrap_or(false);
+
+        match self.get_access().and_then(|db| db.delete_replication_forum_pair(pair.id, cascade)) {
+            Ok(_) => {
+                write_info_log(format!("Replication pair removed {:?}", pair));
+                format!("Replication pair removed: {}", describe_pair(&ctx.cache, &pair))
+            }
+            Err(err) if matches!(err.err_type, ErrorType::BadRequest) => {
+                format!("Replication pair {} still has replicated threads, remove it with `cascade`", pair.id)
+            }
+            Err(err) => format!("Error removing replication pair: {}", err.message),
+        }
+    }
+
+    fn pair_info(&self, ctx: &Context, guild_id: i64, options: &[CommandDataOption]) -> String {
+        match self.pair_of_guild(guild_id, options) {
+            Ok(pair) => format!(
+                "{}\ncreated: {}\non_delete: `{}`\nattachments: `{}`\nwebhook: `{}`\nreactions: `{}`\non_expiry: `{}`\napproval: `{}`\nthread_sync: `{}`\norigin_label: `{}`\nbroken: {}\nrecent requests:\n{}",
+                describe_pair(&ctx.cache, &pair),
+                pair.created_at.format("%Y-%m-%d %H:%M"),
+                pair.on_message_delete,
+                pair.attachment_fallback,
+                if pair.use_webhook { "on" } else { "off" },
+                if pair.mirror_reactions { "on" } else { "off" },
+                pair.on_prompt_expiry,
+                pair.approval_policy,
+                pair.thread_sync,
+                pair.origin_label,
+                pair.broken_reason.as_deref().unwrap_or("no"),
+                self.status_history(pair.id),
+            ),
+            Err(message) => message,
+        }
+    }
+
+    // The last status changes of the pair's replication requests, one line each.
+    fn status_history(&self, pair_id: i64) -> String {
+        let events = match self.get_access().and_then(|db| db.get_replication_status_events_for_pair(pair_id, STATUS_HISTORY_LENGTH)) {
+            Ok(events) if events.is_empty() => return "none".to_string(),
+            Ok(events) => events,
+            Err(err) => return format!("Error getting the history: {}", err.message),
+        };
+
+        events.iter()
+            .map(|(event, reply)| {
+                let mut line = format!(
+                    "`{}` <#{}>: {} -> {}",
+                    event.created_at.format("%Y-%m-%d %H:%M"),
+                    reply.channel_id,
+                    event.from_status.as_deref().unwrap_or("new"),
+                    event.to_status,
+                );
+                if let Some(actor) = event.actor_id {
+                    line.push_str(&format!(" by <@{}>", actor));
+                }
+                if let Some(reason) = &event.reason {
+                    line.push_str(&format!(" ({})", reason));
+                }
+                line
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    // Pair from the `pair_id` option, only if this server is one of its sides.
+    fn pair_of_guild(&self, guild_id: i64, options: &[CommandDataOption]) -> Result<ReplicationForumPair, String> {
+        let pair_id = option_int(options, "pair_id").ok_or_else(|| "Missing pair_id".to_string())?;
+
+        match self.get_access().and_then(|db| db.get_replication_forum_pair_by_id(pair_id)) {
+            Ok(pair) if pair.from_guild == guild_id || pair.to_guild == guild_id => Ok(pair),
+            _ => Err(format!("No replication pair {} in this server", pair_id)),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use serenity::all::{ChannelId, ChannelType, Context, Guild, GuildChannel, GuildId, Member, PermissionOverwriteType, Permissions, RoleId, UserId};
+use crate::errors::{AppError, ErrorType};
+
+// What the bot does in the source forum: post the pairing prompt and replicate replies back.
+const SOURCE_PERMISSIONS: Permissions = Permissions::VIEW_CHANNEL
+    .union(Permissions::READ_MESSAGE_HISTORY)
+    .union(Permissions::SEND_MESSAGES_IN_THREADS)
+    .union(Permissions::ADD_REACTIONS);
+
+// What the bot does in the target forum: create the replicated posts and write in them.
+const TARGET_PERMISSIONS: Permissions = Permissions::VIEW_CHANNEL
+    .union(Permissions::READ_MESSAGE_HISTORY)
+    .union(Permissions::SEND_MESSAGES)
+    .union(Permissions::SEND_MESSAGES_IN_THREADS)
+    .union(Permissions::ATTACH_FILES);
+
+// Text channel pairs only read on one side and write on the other.
+const CHANNEL_SOURCE_PERMISSIONS: Permissions = Permissions::VIEW_CHANNEL
+    .union(Permissions::READ_MESSAGE_HISTORY)
+    .union(Permissions::ADD_REACTIONS);
+
+const CHANNEL_TARGET_PERMISSIONS: Permissions = Permissions::VIEW_CHANNEL
+    .union(Permissions::READ_MESSAGE_HISTORY)
+    .union(Permissions::SEND_MESSAGES)
+    .union(Permissions::ATTACH_FILES);
+
+fn cached_guild(ctx: &Context, guild_id: i64) -> Result<Guild, AppError> {
+    if guild_id <= 0 {
+        return Err(AppError::new(format!("`{}` is not a server id", guild_id).as_str(), ErrorType::Validation));
+    }
+
+    match ctx.cache.guild(guild_id as u64) {
+        Some(guild) => Ok(guild.clone()),
+        None => Err(AppError::new(format!("The bot is not in server `{}`", guild_id).as_str(), ErrorType::NotFound)),
+    }
+}
+
+fn channel_of(guild: &Guild, channel_id: i64, kinds: &[ChannelType], expected: &str) -> Result<GuildChannel, AppError> {
+    let channel = match u64::try_from(channel_id).ok().filter(|id| *id != 0).and_then(|id| guild.channels.get(&ChannelId::new(id))) {
+        Some(channel) => channel,
+        None => return Err(AppError::new(format!("Channel `{}` not found in {}", channel_id, guild.name).as_str(), ErrorType::NotFound)),
+    };
+
+    if !kinds.contains(&channel.kind) {
+        return Err(AppError::new(format!("#{} in {} is not {}", channel.name, guild.name, expected).as_str(), ErrorType::Validation));
+    }
+
+    Ok(channel.clone())
+}
+
+async fn member_of(ctx: &Context, guild: &Guild, user_id: UserId) -> Option<Member> {
+    match guild.members.get(&user_id) {
+        Some(member) => Some(member.clone()),
+        None => GuildId::new(guild.id.get()).member(&ctx.http, user_id).await.ok(),
+    }
+}
+
+fn check_bot_permissions(guild: &Guild, forum: &GuildChannel, bot: &Member, required: Permissions) -> Result<(), AppError> {
+    let missing = required - guild.user_permissions_in(forum, bot);
+    if !missing.is_empty() {
+        return Err(AppError::new(
+            format!("The bot is missing permissions in #{} of {}: {}", forum.name, guild.name, missing.get_permission_names().join(", ")).as_str(),
+            ErrorType::Forbidden,
+        ));
+    }
+
+    Ok(())
+}
+
+async fn check_administrator(ctx: &Context, guild: &Guild, user_id: UserId) -> Result<(), AppError> {
+    let is_admin = match member_of(ctx, guild, user_id).await {
+        Some(member) => guild.member_permissions(&member).administrator(),
+        None => false,
+    };
+
+    if !is_admin {
+        return Err(AppError::new(format!("You must be an administrator of {} to pair its channels", guild.name).as_str(), ErrorType::Unauthorized));
+    }
+
+    Ok(())
+}
+
+/// Checks a forum pair against the cache and the API before it is persisted: the bot is in both
+/// servers, both channels are forums, the bot can work in them and `user_id` administers both sides.
+pub async fn validate_forum_pair(ctx: &Context, user_id: UserId, from_guild: i64, from_forum: i64, to_guild: i64, to_forum: i64) -> Result<(), AppError> {
+    if from_forum == to_forum {
+        return Err(AppError::new("A forum cannot be paired with itself", ErrorType::Validation));
+    }
+
+    let source = cached_guild(ctx, from_guild)?;
+    let target = cached_guild(ctx, to_guild)?;
+    let source_forum = channel_of(&source, from_forum, &[ChannelType::Forum], "a forum channel")?;
+    let target_forum = channel_of(&target, to_forum, &[ChannelType::Forum], "a forum channel")?;
+
+    check_pair_access(ctx, user_id, (&source, &source_forum, SOURCE_PERMISSIONS), (&target, &target_forum, TARGET_PERMISSIONS)).await
+}
+
+/// Same checks as `validate_forum_pair` for a pair of text or announcement channels.
+pub async fn validate_channel_pair(ctx: &Context, user_id: UserId, from_guild: i64, from_channel: i64, to_guild: i64, to_channel: i64) -> Result<(), AppError> {
+    if from_channel == to_channel {
+        return Err(AppError::new("A channel cannot be paired with itself", ErrorType::Validation));
+    }
+
+    let kinds = [ChannelType::Text, ChannelType::News];
+    let source = cached_guild(ctx, from_guild)?;
+    let target = cached_guild(ctx, to_guild)?;
+    let source_channel = channel_of(&source, from_channel, &kinds, "a text or announcement channel")?;
+    let target_channel = channel_of(&target, to_channel, &kinds, "a text or announcement channel")?;
+
+    check_pair_access(ctx, user_id, (&source, &source_channel, CHANNEL_SOURCE_PERMISSIONS), (&target, &target_channel, CHANNEL_TARGET_PERMISSIONS)).await
+}
+
+// The bot can work in both channels and `user_id` administers both servers.
+async fn check_pair_access(ctx: &Context, user_id: UserId, source: (&Guild, &GuildChannel, Permissions), target: (&Guild, &GuildChannel, Permissions)) -> Result<(), AppError> {
+    check_bot_access(ctx, source, target).await?;
+    check_administrator(ctx, source.0, user_id).await?;
+    check_administrator(ctx, target.0, user_id).await?;
+
+    Ok(())
+}
+
+async fn check_bot_access(ctx: &Context, source: (&Guild, &GuildChannel, Permissions), target: (&Guild, &GuildChannel, Permissions)) -> Result<(), AppError> {
+    let bot_id = ctx.cache.current_user().id;
+    for (guild, channel, required) in [source, target] {
+        let bot = member_of(ctx, guild, bot_id).await
+            .ok_or_else(|| AppError::new(format!("The bot is not a member of {}", guild.name).as_str(), ErrorType::NotFound))?;
+        check_bot_permissions(guild, channel, &bot, required)?;
+    }
+
+    Ok(())
+}
+
+/// Whether @everyone can see `channel`, from the @everyone role and its overwrite on the channel.
+pub fn is_public(guild: &Guild, channel: &GuildChannel) -> bool {
+    let everyone = RoleId::new(guild.id.get());
+    let mut permissions = guild.roles.get(&everyone).map_or(Permissions::empty(), |role| role.permissions);
+    if let Some(overwrite) = channel.permission_overwrites.iter().find(|o| o.kind == PermissionOverwriteType::Role(everyone)) {
+        permissions = (permissions - overwrite.deny) | overwrite.allow;
+    }
+
+    permissions.administrator() || permissions.view_channel()
+}
+
+/// Checks a pair created by a mirror before it is persisted: the bot can work in both channels.
+/// The administrator of both sides was checked when the mirror was created.
+pub async fn validate_mirrored_pair(ctx: &Context, source: &GuildChannel, target: &GuildChannel) -> Result<(), AppError> {
+    let source_guild = cached_guild(ctx, source.guild_id.get() as i64)?;
+    let target_guild = cached_guild(ctx, target.guild_id.get() as i64)?;
+    let (source_permissions, target_permissions) = match source.kind {
+        ChannelType::Forum => (SOURCE_PERMISSIONS, TARGET_PERMISSIONS),
+        _ => (CHANNEL_SOURCE_PERMISSIONS, CHANNEL_TARGET_PERMISSIONS),
+    };
+
+    check_bot_access(ctx, (&source_guild, source, source_permissions), (&target_guild, target, target_permissions)).await
+}
+
+/// Checks a mirror before it is persisted: the bot is in both servers, `from_category` is a category
+/// of the source, the bot can create channels in the target and `user_id` administers both sides.
+pub async fn validate_mirror(ctx: &Context, user_id: UserId, from_guild: i64, from_category: Option<i64>, to_guild: i64) -> Result<(), AppError> {
+    if from_guild == to_guild {
+        return Err(AppError::new("A server cannot be mirrored into itself", ErrorType::Validation));
+    }
+
+    let source = cached_guild(ctx, from_guild)?;
+    let target = cached_guild(ctx, to_guild)?;
+    if let Some(category) = from_category {
+        channel_of(&source, category, &[ChannelType::Category], "a category")?;
+    }
+
+    let bot_id = ctx.cache.current_user().id;
+    let target_bot = member_of(ctx, &target, bot_id).await
+        .ok_or_else(|| AppError::new(format!("The bot is not a member of {}", target.name).as_str(), ErrorType::NotFound))?;
+    if !target.member_permissions(&target_bot).manage_channels() {
+        return Err(AppError::new(format!("The bot is missing permissions in {}: Manage Channels", target.name).as_str(), ErrorType::Forbidden));
+    }
+
+    check_administrator(ctx, &source, user_id).await?;
+    check_administrator(ctx, &target, user_id).await?;
+
+    Ok(())
+}
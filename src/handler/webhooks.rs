@@ -0,0 +1,94 @@
+use serenity::all::{ChannelId, Context, CreateAttachment, CreateWebhook, ExecuteWebhook, GuildChannel, Message, Webhook};
+use crate::errors::{AppError, ErrorType};
+use crate::handler::content::no_mentions;
+use crate::handler::db_access::ReplicationWebhookData;
+use crate::handler::Handler;
+use crate::log::{write_error_log, write_info_log};
+
+const WEBHOOK_NAME: &str = "Replication";
+
+// Name shown on a webhook-replicated message: server nickname, then display name, then username.
+pub fn author_display_name(msg: &Message) -> String {
+    msg.member.as_ref().and_then(|m| m.nick.clone())
+        .or_else(|| msg.author.global_name.clone())
+        .unwrap_or_else(|| msg.author.name.clone())
+}
+
+impl Handler {
+    /// Webhook used to replicate into `channel_id` (a forum, threads are targeted with `in_thread`).
+    /// Looked up in memory, then in the database, and created on the channel as a last resort.
+    /// The cache stays locked throughout so that concurrent messages never create two webhooks.
+    pub(crate) async fn webhook_for_channel(&self, ctx: &Context, guild_id: i64, channel_id: u64) -> Result<Webhook, AppError> {
+        let mut webhooks = self.webhooks.lock().await;
+        if let Some(webhook) = webhooks.get(&channel_id) {
+            return Ok(webhook.clone());
+        }
+
+        let stored = self.get_access()?.get_replication_webhook(channel_id as i64);
+        if let Ok(stored) = stored {
+            match Webhook::from_id_with_token(&ctx.http, stored.webhook_id as u64, &stored.webhook_token).await {
+                Ok(webhook) => {
+                    webhooks.insert(channel_id, webhook.clone());
+                    return Ok(webhook);
+                }
+                Err(why) => {
+                    // Deleted from the channel settings, forget it and create a new one
+                    write_error_log(format!("Stored webhook {} for {} is unusable: {why:?}", stored.webhook_id, channel_id));
+                    self.get_access()?.delete_replication_webhook(channel_id as i64)?;
+                }
+            }
+        }
+
+        let webhook = ChannelId::new(channel_id)
+            .create_webhook(&ctx.http, CreateWebhook::new(WEBHOOK_NAME))
+            .await
+            .map_err(|why| AppError::new(format!("Error creating webhook in {}: {why:?}", channel_id).as_str(), ErrorType::DistantServer))?;
+
+        // The token is only exposed through the execute url
+        let token = match webhook.url() {
+            Ok(url) => url.rsplit('/').next().unwrap_or_default().to_string(),
+            Err(_) => return Err(AppError::new("Created webhook has no token", ErrorType::DistantServer)),
+        };
+
+        self.get_access()?.create_replication_webhook(ReplicationWebhookData {
+            guild_id,
+            channel_id: channel_id as i64,
+            webhook_id: webhook.id.get() as i64,
+            webhook_token: token,
+        })?;
+        write_info_log(format!("Webhook {} created for channel {}", webhook.id, channel_id));
+
+        webhooks.insert(channel_id, webhook.clone());
+        Ok(webhook)
+    }
+
+    /// Webhook that posted a replicated message, from the id stored in replication_message_pairs.
+    pub(crate) async fn webhook_by_id(&self, ctx: &Context, webhook_id: i64) -> Result<Webhook, AppError> {
+        let stored = self.get_access()?.get_replication_webhook_by_webhook_id(webhook_id)?;
+        self.webhook_for_channel(ctx, stored.guild_id, stored.channel_id as u64).await
+    }
+
+    /// Posts `content` in `thread` through its forum's webhook, as the author of `msg`.
+    pub(crate) async fn execute_in_thread(&self, ctx: &Context, thread: &GuildChannel, msg: &Message, content: String, files: Vec<CreateAttachment>) -> Result<Message, AppError> {
+        let forum = match thread.parent_id {
+            Some(forum) => forum,
+            None => return Err(AppError::new(format!("Thread {} has no parent forum", thread.id).as_str(), ErrorType::NotFound)),
+        };
+
+        let webhook = self.webhook_for_channel(ctx, thread.guild_id.get() as i64, forum.get()).await?;
+
+        let builder = ExecuteWebhook::new()
+            .content(content)
+            .username(author_display_name(msg))
+            .avatar_url(msg.author.face())
+            .in_thread(thread.id)
+            .add_files(files)
+            .allowed_mentions(no_mentions());
+
+        match webhook.execute(&ctx.http, true, builder).await {
+            Ok(Some(message)) => Ok(message),
+            Ok(None) => Err(AppError::new("Webhook did not return the sent message", ErrorType::DistantServer)),
+            Err(why) => Err(AppError::new(format!("Error executing webhook: {why:?}").as_str(), ErrorType::DistantServer)),
+        }
+    }
+}
@@ -101,8 +101,10 @@ async fn main() {
 
     let db_handler = Arc::new(handle_database_init());
 
+    let handler = Handler::new(db_handler.clone());
+
     let mut client = Client::builder(&token, intents)
-        .event_handler(Handler::new(db_handler.clone()))
+        .event_handler(handler.clone())
         .framework(framework)
         .type_map_insert::<CommandCounter>(HashMap::default())
         .await
@@ -115,6 +117,7 @@ async fn main() {
         write_info_log(format!("Inserting ShardManager into data map -> {:?}", shard_manager));
         data.insert::<ShardManagerContainer>(shard_manager);
         data.insert::<DbHandler>(db_handler.clone());
+        data.insert::<Handler>(handler);
     }
 
     // Here we clone a lock to the Shard Manager, and then move it into a new thread. The thread
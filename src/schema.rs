@@ -1,3 +1,119 @@
+table! {
+    replication_backfill_jobs (id) {
+        id -> Int8,
+        from_guild -> Int8,
+        from_thread -> Int8,
+        to_guild -> Int8,
+        to_thread -> Int8,
+        last_message -> Nullable<Int8>,
+        status -> Varchar,
+        error -> Nullable<Varchar>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    replication_channel_pairs (id) {
+        id -> Int8,
+        from_guild -> Int8,
+        from_channel -> Int8,
+        to_guild -> Int8,
+        to_channel -> Int8,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    replication_filters (id) {
+        id -> Int8,
+        pair_id -> Int8,
+        kind -> Varchar,
+        value -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    replication_message_pairs (id) {
+        id -> Int8,
+        from_guild -> Int8,
+        from_channel -> Int8,
+        from_message -> Int8,
+        to_guild -> Int8,
+        to_channel -> Int8,
+        to_message -> Int8,
+        created_at -> Timestamp,
+        webhook_id -> Nullable<Int8>,
+        origin_guild -> Int8,
+        origin_channel -> Int8,
+        origin_message -> Int8,
+        hops -> Int4,
+    }
+}
+
+table! {
+    replication_mirror_channels (id) {
+        id -> Int8,
+        mirror_id -> Int8,
+        from_channel -> Int8,
+        to_channel -> Int8,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    replication_mirrors (id) {
+        id -> Int8,
+        from_guild -> Int8,
+        from_category -> Nullable<Int8>,
+        to_guild -> Int8,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    replication_moderation_channels (id) {
+        id -> Int8,
+        guild_id -> Int8,
+        channel_id -> Int8,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    replication_queue (id) {
+        id -> Int8,
+        replication_reply_id -> Int8,
+        guild_id -> Int8,
+        channel_id -> Int8,
+        message_id -> Nullable<Int8>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    replication_reaction_counts (id) {
+        id -> Int8,
+        channel_id -> Int8,
+        message_id -> Int8,
+        emoji -> Varchar,
+        count -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    replication_status_events (id) {
+        id -> Int8,
+        replication_reply_id -> Int8,
+        from_status -> Nullable<Varchar>,
+        to_status -> Varchar,
+        actor_id -> Nullable<Int8>,
+        reason -> Nullable<Varchar>,
+        created_at -> Timestamp,
+    }
+}
+
 table! {
     replication_thread_pairs (id) {
         id -> Int8,
@@ -10,6 +126,17 @@ table! {
     }
 }
 
+table! {
+    replication_webhooks (id) {
+        id -> Int8,
+        guild_id -> Int8,
+        channel_id -> Int8,
+        webhook_id -> Int8,
+        webhook_token -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
 table! {
     replications_forum_pairs (id) {
         id -> Int8,
@@ -18,13 +145,21 @@ table! {
         to_guild -> Int8,
         to_forum -> Int8,
         created_at -> Timestamp,
+        on_message_delete -> Varchar,
+        attachment_fallback -> Varchar,
+        use_webhook -> Bool,
+        mirror_reactions -> Bool,
+        on_prompt_expiry -> Varchar,
+        approval_policy -> Varchar,
+        thread_sync -> Varchar,
+        broken_reason -> Nullable<Varchar>,
+        origin_label -> Varchar,
     }
 }
 
 table! {
     replications_reply (id) {
         id -> Int8,
-        responded -> Bool,
         status -> Varchar,
         guild_id -> Int8,
         created_at -> Timestamp,
@@ -32,14 +167,30 @@ table! {
         replication_pairs -> Int8,
         message_id -> Nullable<Int8>,
         message_owner -> Int8,
+        reminded_at -> Nullable<Timestamp>,
     }
 }
 
+joinable!(replication_filters -> replications_forum_pairs (pair_id));
+joinable!(replication_mirror_channels -> replication_mirrors (mirror_id));
+joinable!(replication_queue -> replications_reply (replication_reply_id));
+joinable!(replication_status_events -> replications_reply (replication_reply_id));
 joinable!(replication_thread_pairs -> replications_reply (replication_reply_id));
 joinable!(replications_reply -> replications_forum_pairs (replication_pairs));
 
 allow_tables_to_appear_in_same_query!(
+    replication_backfill_jobs,
+    replication_channel_pairs,
+    replication_filters,
+    replication_message_pairs,
+    replication_mirror_channels,
+    replication_mirrors,
+    replication_moderation_channels,
+    replication_queue,
+    replication_reaction_counts,
+    replication_status_events,
     replication_thread_pairs,
+    replication_webhooks,
     replications_forum_pairs,
     replications_reply,
 );